@@ -0,0 +1,63 @@
+-- Schema used by the PostgreSQL sink.
+--
+-- Hashes, addresses and blob data are stored as raw bytes and 256-bit integers as
+-- NUMERIC(78, 0). Blocks are never deleted: reorged blocks are flagged as
+-- non-canonical and flagged back as canonical when they are forwarded again.
+
+CREATE TABLE blocks (
+    hash BYTEA PRIMARY KEY,
+    number BIGINT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    slot INTEGER NOT NULL,
+    blob_gas_used NUMERIC(78, 0) NOT NULL,
+    excess_blob_gas NUMERIC(78, 0) NOT NULL,
+    canonical BOOLEAN NOT NULL DEFAULT TRUE,
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX blocks_slot_idx ON blocks (slot);
+CREATE INDEX blocks_number_idx ON blocks (number);
+
+-- Blob transactions. A transaction points to the last block it was indexed with.
+CREATE TABLE transactions (
+    hash BYTEA PRIMARY KEY,
+    block_hash BYTEA NOT NULL REFERENCES blocks (hash),
+    block_number BIGINT NOT NULL,
+    index BIGINT NOT NULL,
+    from_address BYTEA NOT NULL,
+    to_address BYTEA,
+    gas_price NUMERIC(78, 0) NOT NULL,
+    max_fee_per_blob_gas NUMERIC(78, 0) NOT NULL,
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX transactions_block_hash_idx ON transactions (block_hash);
+
+-- Blobs are unique by versioned hash and can be referenced by several transactions.
+CREATE TABLE blobs (
+    versioned_hash BYTEA PRIMARY KEY,
+    commitment TEXT NOT NULL,
+    proof TEXT NOT NULL,
+    data BYTEA NOT NULL,
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE blobs_on_transactions (
+    tx_hash BYTEA NOT NULL REFERENCES transactions (hash),
+    index INTEGER NOT NULL,
+    blob_versioned_hash BYTEA NOT NULL REFERENCES blobs (versioned_hash),
+    PRIMARY KEY (tx_hash, index)
+);
+
+-- Single row table holding the indexer's sync progress.
+CREATE TABLE blockchain_sync_state (
+    id INTEGER PRIMARY KEY DEFAULT 1 CHECK (id = 1),
+    last_finalized_block INTEGER,
+    last_lower_synced_slot INTEGER,
+    last_upper_synced_slot INTEGER,
+    last_upper_synced_block_root BYTEA,
+    last_upper_synced_block_slot INTEGER,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,7 @@
+-- Blob data can be kept in an external blob storage, in which case only a reference
+-- to its location is stored.
+
+ALTER TABLE blobs ALTER COLUMN data DROP NOT NULL;
+ALTER TABLE blobs ADD COLUMN data_reference TEXT;
+ALTER TABLE blobs ADD CONSTRAINT blobs_data_or_reference_check
+    CHECK (data IS NOT NULL OR data_reference IS NOT NULL);
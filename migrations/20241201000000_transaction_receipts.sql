@@ -0,0 +1,8 @@
+-- Receipt-derived fields reflect what a transaction actually paid. They are nullable as
+-- transactions indexed before they were introduced don't have them.
+
+ALTER TABLE transactions ADD COLUMN effective_gas_price NUMERIC(78, 0);
+ALTER TABLE transactions ADD COLUMN gas_used NUMERIC(78, 0);
+ALTER TABLE transactions ADD COLUMN blob_gas_price NUMERIC(78, 0);
+ALTER TABLE transactions ADD COLUMN blob_gas_used NUMERIC(78, 0);
+ALTER TABLE transactions ADD COLUMN status BOOLEAN;
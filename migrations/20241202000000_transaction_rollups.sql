@@ -0,0 +1,4 @@
+-- Rollup attribution of blob transactions, based on their sender address.
+
+ALTER TABLE transactions ADD COLUMN category TEXT;
+ALTER TABLE transactions ADD COLUMN rollup TEXT;
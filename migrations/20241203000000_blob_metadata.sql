@@ -0,0 +1,6 @@
+-- Statistics about the content of blobs, only filled in when blob analysis is enabled.
+
+ALTER TABLE blobs ADD COLUMN used_bytes INTEGER;
+ALTER TABLE blobs ADD COLUMN payload_length INTEGER;
+ALTER TABLE blobs ADD COLUMN zero_byte_ratio DOUBLE PRECISION;
+ALTER TABLE blobs ADD COLUMN encoding TEXT;
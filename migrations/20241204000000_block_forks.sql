@@ -0,0 +1,3 @@
+-- Consensus layer fork each block belongs to.
+
+ALTER TABLE blocks ADD COLUMN fork TEXT;
@@ -0,0 +1,3 @@
+-- Blob base fee resulting from the excess blob gas of each block.
+
+ALTER TABLE blocks ADD COLUMN blob_base_fee NUMERIC(78, 0);
@@ -0,0 +1,8 @@
+-- Slot ranges that failed to be indexed, waiting to be retried.
+
+CREATE TABLE failed_slots_chunks (
+    id SERIAL PRIMARY KEY,
+    initial_slot INTEGER NOT NULL,
+    final_slot INTEGER NOT NULL,
+    inserted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -1,32 +1,230 @@
-use clap::{ArgAction, Parser};
+use std::path::PathBuf;
+
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 
 use crate::clients::beacon::types::BlockId;
 
 /// Blobscan's indexer for the EIP-4844 upgrade.
+///
+/// When no command is given, the indexer syncs historical blocks and follows the chain head
+/// as the `sync` command does, with the behaviour tweaked by the flags below.
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    after_help = "Options shared with the commands go after the command name, e.g. `blob-indexer sync --dry-run`"
+)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// TOML config file. Its settings are overridden by environment variables and CLI arguments
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
     /// Slot to start indexing from
     #[arg(short, long)]
     pub from_slot: Option<BlockId>,
 
-    /// Slot to stop indexing at
+    /// Slot to stop indexing at. Disables live indexing
     #[arg(short, long)]
     pub to_slot: Option<BlockId>,
 
     /// Number of threads used for parallel indexing
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub num_threads: Option<u32>,
 
     /// Amount of slots to be processed before saving latest slot in the database
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub slots_per_save: Option<u32>,
 
     /// Disable slot checkpoint saving when syncing
-    #[arg(short = 'c', long, action = ArgAction::SetTrue)]
+    #[arg(short = 'c', long, action = ArgAction::SetTrue, global = true)]
     pub disable_sync_checkpoint_save: bool,
 
+    /// Log the operations that would be written to the sink instead of writing them
+    #[arg(long, action = ArgAction::SetTrue, global = true)]
+    pub dry_run: bool,
+
+    /// JSON Lines file where the operations skipped by the dry run are recorded
+    #[arg(long, global = true, requires = "dry_run")]
+    pub dry_run_output: Option<String>,
+
     /// Disable historical synchronization
-    #[arg(short = 'd', long, action = ArgAction::SetTrue)]
+    #[arg(short = 'd', long, action = ArgAction::SetTrue, conflicts_with = "to_slot")]
     pub disable_sync_historical: bool,
 }
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Sync historical blocks and follow the chain head
+    Sync {
+        /// Slot to start indexing from
+        #[arg(short, long)]
+        from_slot: Option<BlockId>,
+    },
+    /// Follow the chain head without syncing historical blocks
+    Live {
+        /// Slot to start following the chain head from
+        #[arg(short, long)]
+        from_slot: Option<BlockId>,
+    },
+    /// Sync historical blocks backwards without following the chain head
+    Backfill {
+        /// Slot to start syncing backwards from. Defaults to the lowest synced slot
+        #[arg(short, long)]
+        from_slot: Option<BlockId>,
+
+        /// Slot to stop syncing at. Defaults to the Dencun fork slot
+        #[arg(short, long)]
+        to_slot: Option<BlockId>,
+    },
+    /// Inspect the indexer state without indexing anything
+    Inspect {
+        #[command(subcommand)]
+        command: InspectCommand,
+    },
+    /// Manage the indexer configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Re-index the slots with blobs that are missing from the sink
+    Repair {
+        /// Slot to start repairing from
+        #[arg(short, long)]
+        from_slot: BlockId,
+
+        /// Slot to stop repairing at (included)
+        #[arg(short, long)]
+        to_slot: BlockId,
+    },
+    /// Manage the slot ranges that failed to be indexed
+    FailedChunks {
+        #[command(subcommand)]
+        command: FailedChunksCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum InspectCommand {
+    /// Print the sync state stored in the sink
+    SyncState,
+    /// Process a block without indexing it and print the resulting index request
+    Slot {
+        /// Block to inspect, given as a slot, block root or `head`/`finalized`
+        block_id: BlockId,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum FailedChunksCommand {
+    /// List the slot ranges queued to be retried
+    List,
+    /// Retry the queued slot ranges, removing the ones indexed from the queue
+    Retry {
+        /// Id of a chunk to retry. Can be given several times. Defaults to all the chunks
+        #[arg(long = "id")]
+        ids: Vec<u32>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Print the effective configuration with secrets redacted
+    Print,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Json,
+    Table,
+}
+
+impl Args {
+    /// Returns the command to run, mapping the top-level flags to the equivalent command when
+    /// none is given.
+    pub fn command(&self) -> Command {
+        if let Some(command) = self.command.clone() {
+            return command;
+        }
+
+        if self.disable_sync_historical {
+            Command::Live {
+                from_slot: self.from_slot.clone(),
+            }
+        } else if self.to_slot.is_some() {
+            Command::Backfill {
+                from_slot: self.from_slot.clone(),
+                to_slot: self.to_slot.clone(),
+            }
+        } else {
+            Command::Sync {
+                from_slot: self.from_slot.clone(),
+            }
+        }
+    }
+}
+
+impl Command {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Sync { .. } => "sync",
+            Command::Live { .. } => "live",
+            Command::Backfill { .. } => "backfill",
+            Command::Inspect { .. } => "inspect",
+            Command::Config { .. } => "config",
+            Command::Repair { .. } => "repair",
+            Command::FailedChunks { .. } => "failed-chunks",
+        }
+    }
+
+    /// Returns whether the command records its progress in the journal. The journal is locked
+    /// while open, so the other commands leave it closed to run alongside a running indexer.
+    pub fn uses_journal(&self) -> bool {
+        matches!(
+            self,
+            Command::Sync { .. } | Command::Live { .. } | Command::Backfill { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::error::ErrorKind;
+
+    use super::*;
+
+    fn parse_command(args: &[&str]) -> Command {
+        Args::try_parse_from([&["blob-indexer"], args].concat())
+            .unwrap()
+            .command()
+    }
+
+    #[test]
+    fn maps_top_level_flags_to_commands() {
+        assert!(matches!(parse_command(&[]), Command::Sync { .. }));
+        assert!(matches!(parse_command(&["-d"]), Command::Live { .. }));
+        assert!(matches!(
+            parse_command(&["--to-slot", "10"]),
+            Command::Backfill {
+                to_slot: Some(BlockId::Slot(10)),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_disabling_historical_sync_with_a_final_slot() {
+        let error = Args::try_parse_from(["blob-indexer", "-d", "--to-slot", "10"]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
+    }
+}
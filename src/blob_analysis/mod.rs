@@ -0,0 +1,413 @@
+use serde::{Deserialize, Serialize};
+
+const BYTES_PER_FIELD_ELEMENT: usize = 32;
+const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+const BYTES_PER_BLOB: usize = BYTES_PER_FIELD_ELEMENT * FIELD_ELEMENTS_PER_BLOB;
+/// Version byte of the OP Stack blob encoding, stored in the second byte of the blob.
+const OP_BLOB_ENCODING_VERSION: u8 = 0;
+/// Rounds of the OP Stack blob encoding, each packing 127 bytes into 4 field elements.
+const OP_BLOB_ENCODING_ROUNDS: usize = 1024;
+/// Maximum data size of an OP Stack encoded blob, as the encoding version and the data length
+/// take 4 bytes of the first round.
+const OP_MAX_BLOB_DATA_SIZE: usize = (4 * 31 + 3) * OP_BLOB_ENCODING_ROUNDS - 4;
+/// Version byte of the derivation frames posted by OP Stack rollups.
+const OP_DERIVATION_VERSION: u8 = 0;
+/// Length of an OP Stack frame header: channel id, frame number and frame data length.
+const OP_FRAME_HEADER_LENGTH: usize = 16 + 2 + 4;
+/// Channel version byte prefixing brotli-compressed OP Stack channels.
+const OP_BROTLI_CHANNEL_VERSION: u8 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlobEncoding {
+    Brotli,
+    Gzip,
+    Zlib,
+    Zstd,
+}
+
+impl BlobEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlobEncoding::Brotli => "brotli",
+            BlobEncoding::Gzip => "gzip",
+            BlobEncoding::Zlib => "zlib",
+            BlobEncoding::Zstd => "zstd",
+        }
+    }
+}
+
+/// Statistics about the content of a blob.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobMetadata {
+    /// Bytes up to the last non-zero one, out of the blob size.
+    pub used_bytes: u32,
+    /// Length of the payload once decoded from the field elements, when the blob uses the OP Stack
+    /// encoding or a zero padding byte per field element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_length: Option<u32>,
+    pub zero_byte_ratio: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<BlobEncoding>,
+}
+
+pub fn analyze_blob(data: &[u8]) -> BlobMetadata {
+    let used_bytes = data
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |position| position + 1);
+    let zero_bytes = data.iter().filter(|byte| **byte == 0).count();
+    let zero_byte_ratio = if data.is_empty() {
+        0.0
+    } else {
+        zero_bytes as f64 / data.len() as f64
+    };
+    let (payload, encoding) = match decode_op_blob(data) {
+        Some(payload) => {
+            let encoding = detect_op_frame_encoding(&payload);
+
+            (Some(payload), encoding)
+        }
+        None => match decode_field_elements(data) {
+            Some(payload) => {
+                let encoding = detect_magic_number(&payload);
+
+                (Some(payload), encoding)
+            }
+            None => (None, detect_magic_number(data)),
+        },
+    };
+
+    BlobMetadata {
+        used_bytes: used_bytes as u32,
+        payload_length: payload.map(|payload| payload.len() as u32),
+        zero_byte_ratio,
+        encoding,
+    }
+}
+
+/// Decodes a blob using the OP Stack blob encoding, where every round of 4 field elements packs
+/// 127 bytes: 31 bytes in each field element plus 3 bytes split into the 6 lower bits of their
+/// first bytes. The first field element also holds the encoding version and the data length.
+///
+/// Returns `None` if the blob doesn't follow the encoding.
+fn decode_op_blob(blob: &[u8]) -> Option<Vec<u8>> {
+    if blob.len() != BYTES_PER_BLOB
+        || blob[0] & 0b1100_0000 != 0
+        || blob[1] != OP_BLOB_ENCODING_VERSION
+    {
+        return None;
+    }
+
+    let data_length = usize::from(blob[2]) << 16 | usize::from(blob[3]) << 8 | usize::from(blob[4]);
+
+    if data_length > OP_MAX_BLOB_DATA_SIZE {
+        return None;
+    }
+
+    let mut output = vec![0u8; OP_MAX_BLOB_DATA_SIZE];
+    let mut encoded_bytes = [blob[0], 0, 0, 0];
+    let mut output_position = 28;
+    let mut input_position = BYTES_PER_FIELD_ELEMENT;
+
+    output[..27].copy_from_slice(&blob[5..BYTES_PER_FIELD_ELEMENT]);
+
+    for round in 0..OP_BLOB_ENCODING_ROUNDS {
+        if round > 0 && output_position >= data_length {
+            break;
+        }
+
+        // The first field element of the first round was already read along with the header
+        let first_field_element = if round == 0 { 1 } else { 0 };
+
+        for encoded_byte in encoded_bytes.iter_mut().skip(first_field_element) {
+            let field_element = &blob[input_position..input_position + BYTES_PER_FIELD_ELEMENT];
+
+            if field_element[0] & 0b1100_0000 != 0 {
+                return None;
+            }
+
+            *encoded_byte = field_element[0];
+            output[output_position..output_position + 31].copy_from_slice(&field_element[1..]);
+            output_position += BYTES_PER_FIELD_ELEMENT;
+            input_position += BYTES_PER_FIELD_ELEMENT;
+        }
+
+        // A round outputs 127 bytes, one less than the field elements it reads
+        output_position -= 1;
+
+        let [a, b, c, d] = encoded_bytes;
+
+        output[output_position - 96] = (a & 0b0011_1111) | ((b & 0b0011_0000) << 2);
+        output[output_position - 64] = (b & 0b0000_1111) | ((d & 0b0000_1111) << 4);
+        output[output_position - 32] = (c & 0b0011_1111) | ((d & 0b0011_0000) << 2);
+    }
+
+    if output[data_length..].iter().any(|byte| *byte != 0)
+        || blob[input_position..].iter().any(|byte| *byte != 0)
+    {
+        return None;
+    }
+
+    output.truncate(data_length);
+
+    Some(output)
+}
+
+/// Strips the padding byte rollups leave at the start of every field element to keep it below
+/// the field modulus, and trims the trailing zero bytes. Returns `None` if some field element
+/// doesn't start with a zero byte, as the blob then uses another encoding.
+fn decode_field_elements(data: &[u8]) -> Option<Vec<u8>> {
+    let mut payload = Vec::with_capacity(data.len());
+
+    for field_element in data.chunks(BYTES_PER_FIELD_ELEMENT) {
+        let (padding, bytes) = field_element.split_first()?;
+
+        if *padding != 0 {
+            return None;
+        }
+
+        payload.extend_from_slice(bytes);
+    }
+
+    let payload_length = payload
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |position| position + 1);
+
+    payload.truncate(payload_length);
+
+    Some(payload)
+}
+
+/// Detects the compression format of the channel an OP Stack derivation frame belongs to. Only
+/// the first frame of a channel starts with the compression header, so frames continuing a
+/// channel are left undetected. Brotli streams have no magic number, so they're detected
+/// through the channel version byte.
+fn detect_op_frame_encoding(data: &[u8]) -> Option<BlobEncoding> {
+    let (&version, frame) = data.split_first()?;
+
+    if version != OP_DERIVATION_VERSION || frame.len() < OP_FRAME_HEADER_LENGTH {
+        return None;
+    }
+
+    let frame_number = u16::from_be_bytes([frame[16], frame[17]]);
+    let frame_data_length =
+        u32::from_be_bytes([frame[18], frame[19], frame[20], frame[21]]) as usize;
+    let frame_data =
+        frame.get(OP_FRAME_HEADER_LENGTH..OP_FRAME_HEADER_LENGTH + frame_data_length)?;
+    let is_last = frame.get(OP_FRAME_HEADER_LENGTH + frame_data_length)?;
+
+    if *is_last > 1 || frame_number != 0 {
+        return None;
+    }
+
+    match frame_data.first() {
+        Some(&OP_BROTLI_CHANNEL_VERSION) => Some(BlobEncoding::Brotli),
+        _ => detect_magic_number(frame_data),
+    }
+}
+
+fn detect_magic_number(data: &[u8]) -> Option<BlobEncoding> {
+    match data {
+        [0x1f, 0x8b, 0x08, ..] => Some(BlobEncoding::Gzip),
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => Some(BlobEncoding::Zstd),
+        // Deflate compression method with a window of up to 32 KiB, no preset dictionary and a
+        // header checksum that is a multiple of 31
+        [cmf, flg, ..]
+            if cmf & 0x0f == 8
+                && cmf >> 4 <= 7
+                && flg & 0x20 == 0
+                && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 =>
+        {
+            Some(BlobEncoding::Zlib)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes data with the OP Stack blob encoding, as the OP Stack batcher does.
+    fn encode_op_blob(data: &[u8]) -> Vec<u8> {
+        let mut input = vec![OP_BLOB_ENCODING_VERSION];
+
+        input.extend_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+        input.extend_from_slice(data);
+        input.resize(input.len().div_ceil(127) * 127, 0);
+
+        let mut blob = vec![0u8; BYTES_PER_BLOB];
+
+        // Each round is laid out as 31 bytes, x, 31 bytes, y, 31 bytes, z and 31 bytes
+        for (round, bytes) in blob
+            .chunks_mut(4 * BYTES_PER_FIELD_ELEMENT)
+            .zip(input.chunks(127))
+        {
+            let (x, y, z) = (bytes[31], bytes[63], bytes[95]);
+            let first_bytes = [
+                x & 0b0011_1111,
+                (y & 0b0000_1111) | ((x & 0b1100_0000) >> 2),
+                z & 0b0011_1111,
+                ((z & 0b1100_0000) >> 2) | ((y & 0b1111_0000) >> 4),
+            ];
+
+            for (i, (field_element, first_byte)) in round
+                .chunks_mut(BYTES_PER_FIELD_ELEMENT)
+                .zip(first_bytes)
+                .enumerate()
+            {
+                field_element[0] = first_byte;
+                field_element[1..].copy_from_slice(&bytes[i * 32..i * 32 + 31]);
+            }
+        }
+
+        blob
+    }
+
+    /// Builds a blob of field elements with a zero padding byte holding the given payload.
+    fn encode_padded_blob(payload: &[u8]) -> Vec<u8> {
+        let mut blob = vec![0u8; BYTES_PER_BLOB];
+
+        for (field_element, bytes) in blob
+            .chunks_mut(BYTES_PER_FIELD_ELEMENT)
+            .zip(payload.chunks(BYTES_PER_FIELD_ELEMENT - 1))
+        {
+            field_element[1..=bytes.len()].copy_from_slice(bytes);
+        }
+
+        blob
+    }
+
+    /// Builds the data posted by an OP Stack batcher: the derivation version followed by a
+    /// single frame.
+    fn op_frame(frame_number: u16, frame_data: &[u8]) -> Vec<u8> {
+        let mut data = vec![OP_DERIVATION_VERSION];
+
+        data.extend_from_slice(&[0xab; 16]);
+        data.extend_from_slice(&frame_number.to_be_bytes());
+        data.extend_from_slice(&(frame_data.len() as u32).to_be_bytes());
+        data.extend_from_slice(frame_data);
+        data.push(1);
+
+        data
+    }
+
+    fn pseudo_random_bytes(length: usize) -> Vec<u8> {
+        (0..length)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 7) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn decodes_op_blobs() {
+        for length in [0, 1, 27, 28, 123, 124, 1000, OP_MAX_BLOB_DATA_SIZE] {
+            let data = pseudo_random_bytes(length);
+
+            assert_eq!(
+                decode_op_blob(&encode_op_blob(&data)),
+                Some(data),
+                "length {length}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_op_blobs() {
+        let blob = encode_op_blob(&pseudo_random_bytes(1000));
+
+        let mut high_bits_set = blob.clone();
+        high_bits_set[BYTES_PER_FIELD_ELEMENT * 2] |= 0b1000_0000;
+        assert_eq!(decode_op_blob(&high_bits_set), None);
+
+        let mut unknown_version = blob.clone();
+        unknown_version[1] = 1;
+        assert_eq!(decode_op_blob(&unknown_version), None);
+
+        let mut trailing_data = blob.clone();
+        trailing_data[BYTES_PER_BLOB - 1] = 1;
+        assert_eq!(decode_op_blob(&trailing_data), None);
+
+        assert_eq!(decode_op_blob(&blob[..BYTES_PER_BLOB / 2]), None);
+    }
+
+    #[test]
+    fn detects_op_brotli_channels() {
+        // Brotli channels start with the channel version byte followed by the brotli stream
+        let mut frame_data = vec![OP_BROTLI_CHANNEL_VERSION, 0x1b, 0xd3, 0x0c];
+        frame_data.extend(pseudo_random_bytes(5000));
+        let data = op_frame(0, &frame_data);
+
+        let metadata = analyze_blob(&encode_op_blob(&data));
+
+        assert_eq!(metadata.encoding, Some(BlobEncoding::Brotli));
+        assert_eq!(metadata.payload_length, Some(data.len() as u32));
+    }
+
+    #[test]
+    fn detects_op_zlib_channels() {
+        // Header written by Go's zlib writer at the best compression level
+        let mut frame_data = vec![0x78, 0xda];
+        frame_data.extend(pseudo_random_bytes(5000));
+
+        let metadata = analyze_blob(&encode_op_blob(&op_frame(0, &frame_data)));
+
+        assert_eq!(metadata.encoding, Some(BlobEncoding::Zlib));
+    }
+
+    #[test]
+    fn leaves_op_continuation_frames_undetected() {
+        let mut frame_data = vec![OP_BROTLI_CHANNEL_VERSION];
+        frame_data.extend(pseudo_random_bytes(5000));
+
+        let metadata = analyze_blob(&encode_op_blob(&op_frame(1, &frame_data)));
+
+        assert_eq!(metadata.encoding, None);
+    }
+
+    #[test]
+    fn detects_padded_payload_encodings() {
+        let zstd = analyze_blob(&encode_padded_blob(&[0x28, 0xb5, 0x2f, 0xfd, 0x04, 0x58]));
+        let gzip = analyze_blob(&encode_padded_blob(&[0x1f, 0x8b, 0x08, 0x00]));
+
+        assert_eq!(zstd.encoding, Some(BlobEncoding::Zstd));
+        assert_eq!(zstd.payload_length, Some(6));
+        assert_eq!(gzip.encoding, Some(BlobEncoding::Gzip));
+    }
+
+    #[test]
+    fn ignores_op_frame_layout_in_padded_payloads() {
+        // Looks like an OP Stack brotli frame once the padding bytes are removed
+        let mut payload = vec![0u8; 23];
+        payload.push(OP_BROTLI_CHANNEL_VERSION);
+        payload.extend(pseudo_random_bytes(100));
+
+        let metadata = analyze_blob(&encode_padded_blob(&payload));
+
+        assert_eq!(metadata.encoding, None);
+    }
+
+    #[test]
+    fn checks_zlib_headers() {
+        assert_eq!(detect_magic_number(&[0x78, 0xda]), Some(BlobEncoding::Zlib));
+        assert_eq!(detect_magic_number(&[0x78, 0x9c]), Some(BlobEncoding::Zlib));
+        assert_eq!(detect_magic_number(&[0x78, 0x01]), Some(BlobEncoding::Zlib));
+        // Window size above 32 KiB
+        assert_eq!(detect_magic_number(&[0x88, 0x1c]), None);
+        // Preset dictionary
+        assert_eq!(detect_magic_number(&[0x78, 0xbb]), None);
+        // Invalid header checksum
+        assert_eq!(detect_magic_number(&[0x78, 0xdb]), None);
+    }
+
+    #[test]
+    fn analyzes_empty_blobs() {
+        let metadata = analyze_blob(&vec![0u8; BYTES_PER_BLOB]);
+
+        assert_eq!(metadata.used_bytes, 0);
+        assert_eq!(metadata.zero_byte_ratio, 1.0);
+        assert_eq!(metadata.encoding, None);
+    }
+}
@@ -0,0 +1,97 @@
+use std::{fmt, fs, path::Path, sync::Arc};
+
+use alloy::primitives::{Bytes, B256};
+use anyhow::Context;
+use object_store::{
+    aws::AmazonS3Builder, local::LocalFileSystem, path::Path as ObjectPath, ObjectStore,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::web3::get_full_hash;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlobStorageType {
+    Filesystem,
+    S3,
+}
+
+pub struct S3Config {
+    pub bucket: String,
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+}
+
+/// Stores blob data outside of the index request, keyed by versioned hash.
+#[derive(Clone)]
+pub struct BlobStorage {
+    store: Arc<dyn ObjectStore>,
+    reference_prefix: String,
+}
+
+impl BlobStorage {
+    pub fn try_new_filesystem(dir: &Path) -> Result<Self, anyhow::Error> {
+        fs::create_dir_all(dir).with_context(|| {
+            format!("Failed to create blob storage directory {}", dir.display())
+        })?;
+
+        let dir = dir.canonicalize()?;
+        let store = LocalFileSystem::new_with_prefix(&dir)?;
+
+        Ok(Self {
+            store: Arc::new(store),
+            reference_prefix: format!("file://{}", dir.display()),
+        })
+    }
+
+    /// Creates an S3-compatible storage. Credentials are read from the standard `AWS_*`
+    /// environment variables.
+    pub fn try_new_s3(config: S3Config) -> Result<Self, anyhow::Error> {
+        let mut builder = AmazonS3Builder::from_env().with_bucket_name(&config.bucket);
+
+        if let Some(endpoint) = config.endpoint {
+            builder = builder
+                .with_allow_http(endpoint.starts_with("http://"))
+                .with_endpoint(endpoint);
+        }
+
+        if let Some(region) = config.region {
+            builder = builder.with_region(region);
+        }
+
+        let store = builder
+            .build()
+            .with_context(|| format!("Failed to create S3 storage for bucket {}", config.bucket))?;
+
+        Ok(Self {
+            store: Arc::new(store),
+            reference_prefix: format!("s3://{}", config.bucket),
+        })
+    }
+
+    /// Stores the blob data and returns a reference to its location.
+    pub async fn store(
+        &self,
+        versioned_hash: &B256,
+        data: &Bytes,
+    ) -> Result<String, anyhow::Error> {
+        let key = get_full_hash(versioned_hash);
+
+        self.store
+            .put(&ObjectPath::from(key.as_str()), data.0.clone().into())
+            .await
+            .with_context(|| format!("Failed to store data of blob {key}"))?;
+
+        Ok(format!("{}/{}", self.reference_prefix, key))
+    }
+}
+
+impl fmt::Debug for BlobStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BlobStorage {{ reference_prefix: {} }}",
+            self.reference_prefix
+        )
+    }
+}
@@ -0,0 +1,387 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use backoff::ExponentialBackoff;
+use futures::future::BoxFuture;
+use reqwest_eventsource::EventSource;
+use tracing::warn;
+
+use crate::clients::common::{ClientError, ClientResult, NumericOrTextCode};
+
+use super::{
+    types::{Blob, Block, BlockHeader, BlockId, DataColumnSidecar, Genesis, Topic},
+    BeaconClient, CommonBeaconClient,
+};
+
+/// Beacon client that routes requests to one of several beacon nodes, failing over to the next
+/// one on connection errors or server errors.
+#[derive(Debug)]
+pub struct FailoverBeaconClient {
+    clients: Vec<BeaconClient>,
+    active_client: AtomicUsize,
+    exp_backoff: Option<ExponentialBackoff>,
+}
+
+impl FailoverBeaconClient {
+    /// Creates a client from the given node clients, which shouldn't retry failed requests
+    /// themselves. Requests are retried with the given backoff once all nodes have failed.
+    pub fn new(clients: Vec<BeaconClient>, exp_backoff: Option<ExponentialBackoff>) -> Self {
+        Self {
+            clients,
+            active_client: AtomicUsize::new(0),
+            exp_backoff,
+        }
+    }
+
+    async fn request<T, F>(&self, f: F) -> ClientResult<T>
+    where
+        F: for<'a> Fn(&'a BeaconClient) -> BoxFuture<'a, ClientResult<T>>,
+    {
+        match self.exp_backoff.clone() {
+            Some(exp_backoff) => {
+                backoff::future::retry_notify(
+                    exp_backoff,
+                    || async {
+                        self.request_any(&f).await.map_err(|error| {
+                            if is_node_failure(&error) {
+                                backoff::Error::transient(error)
+                            } else {
+                                backoff::Error::permanent(error)
+                            }
+                        })
+                    },
+                    |error, duration: std::time::Duration| {
+                        warn!(
+                            ?error,
+                            "All beacon nodes failed. Retrying in {} seconds…",
+                            duration.as_secs()
+                        );
+                    },
+                )
+                .await
+            }
+            None => self.request_any(&f).await,
+        }
+    }
+
+    /// Sends the request to the active node, failing over to the following ones until one of
+    /// them succeeds.
+    async fn request_any<T, F>(&self, f: &F) -> ClientResult<T>
+    where
+        F: for<'a> Fn(&'a BeaconClient) -> BoxFuture<'a, ClientResult<T>>,
+    {
+        let active_client = self.active_client.load(Ordering::Relaxed);
+        let mut last_error = None;
+
+        for i in 0..self.clients.len() {
+            let index = (active_client + i) % self.clients.len();
+            let client = &self.clients[index];
+
+            match f(client).await {
+                Err(error) if is_node_failure(&error) => {
+                    warn!(
+                        node = %client.base_url,
+                        ?error,
+                        "Beacon node request failed. Failing over to the next node…"
+                    );
+
+                    last_error = Some(error);
+                }
+                result => {
+                    if index != active_client {
+                        self.active_client.store(index, Ordering::Relaxed);
+                    }
+
+                    return result;
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No beacon nodes configured").into()))
+    }
+}
+
+#[async_trait]
+impl CommonBeaconClient for FailoverBeaconClient {
+    async fn get_block(&self, block_id: BlockId) -> ClientResult<Option<Block>> {
+        self.request(|client| client.get_block(block_id.clone()))
+            .await
+    }
+
+    async fn get_block_header(&self, block_id: BlockId) -> ClientResult<Option<BlockHeader>> {
+        self.request(|client| client.get_block_header(block_id.clone()))
+            .await
+    }
+
+    async fn get_blobs(&self, block_id: BlockId) -> ClientResult<Option<Vec<Blob>>> {
+        self.request(|client| client.get_blobs(block_id.clone()))
+            .await
+    }
+
+    async fn get_data_column_sidecars(
+        &self,
+        block_id: BlockId,
+    ) -> ClientResult<Option<Vec<DataColumnSidecar>>> {
+        self.request(|client| client.get_data_column_sidecars(block_id.clone()))
+            .await
+    }
+
+    async fn get_genesis(&self) -> ClientResult<Option<Genesis>> {
+        self.request(|client| client.get_genesis()).await
+    }
+
+    fn subscribe_to_events(&self, topics: &[Topic]) -> ClientResult<EventSource> {
+        self.clients[self.active_client.load(Ordering::Relaxed)].subscribe_to_events(topics)
+    }
+
+    fn fail_over(&self) -> bool {
+        if self.clients.len() < 2 {
+            return false;
+        }
+
+        let next_client = (self.active_client.load(Ordering::Relaxed) + 1) % self.clients.len();
+
+        self.active_client.store(next_client, Ordering::Relaxed);
+
+        true
+    }
+}
+
+/// Whether the error is caused by the node being unreachable or unhealthy, rather than by the
+/// request itself. Unexpected responses aren't, as every node would likely return them too.
+fn is_node_failure(error: &ClientError) -> bool {
+    match error {
+        ClientError::Reqwest(error) => {
+            error.is_connect()
+                || error.is_timeout()
+                || error.is_request()
+                || error
+                    .status()
+                    .is_some_and(|status| status.is_server_error())
+        }
+        ClientError::ApiError(response) => match &response.code {
+            NumericOrTextCode::Number(code) => *code >= 500,
+            NumericOrTextCode::String(code) => code.parse::<u16>().is_ok_and(|code| code >= 500),
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use axum::{
+        extract::State,
+        http::{header, StatusCode},
+        routing::get,
+        Json, Router,
+    };
+    use futures::StreamExt;
+    use reqwest::Client;
+    use reqwest_eventsource::Event;
+    use serde_json::{json, Value};
+
+    use crate::clients::{
+        beacon::Config,
+        common::{ErrorResponse, NumericOrTextCode},
+    };
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct MockBeaconNode {
+        genesis_requests: Arc<AtomicUsize>,
+        event_subscriptions: Arc<AtomicUsize>,
+        genesis_response: Option<(StatusCode, &'static str)>,
+    }
+
+    async fn genesis_handler(State(node): State<MockBeaconNode>) -> (StatusCode, String) {
+        node.genesis_requests.fetch_add(1, Ordering::Relaxed);
+
+        match node.genesis_response {
+            Some((status, body)) => (status, body.to_string()),
+            None => (
+                StatusCode::OK,
+                json!({ "data": { "genesis_time": "1606824023" } }).to_string(),
+            ),
+        }
+    }
+
+    async fn events_handler(
+        State(node): State<MockBeaconNode>,
+    ) -> ([(header::HeaderName, &'static str); 1], &'static str) {
+        node.event_subscriptions.fetch_add(1, Ordering::Relaxed);
+
+        (
+            [(header::CONTENT_TYPE, "text/event-stream")],
+            ": connected\n\n",
+        )
+    }
+
+    async fn slow_handler() -> Json<Value> {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        Json(Value::Null)
+    }
+
+    async fn start_mock_beacon_node(node: MockBeaconNode) -> SocketAddr {
+        let app = Router::new()
+            .route("/eth/v1/beacon/genesis", get(genesis_handler))
+            .route("/eth/v1/events", get(events_handler))
+            .route("/slow", get(slow_handler))
+            .with_state(node);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        addr
+    }
+
+    fn beacon_client(base_url: String) -> BeaconClient {
+        BeaconClient::try_with_client(
+            Client::new(),
+            Config {
+                base_url,
+                exp_backoff: None,
+            },
+        )
+        .unwrap()
+    }
+
+    async fn create_failover_client(nodes: &[MockBeaconNode]) -> FailoverBeaconClient {
+        let mut clients = vec![];
+
+        for node in nodes {
+            let addr = start_mock_beacon_node(node.clone()).await;
+
+            clients.push(beacon_client(format!("http://{addr}")));
+        }
+
+        FailoverBeaconClient::new(clients, None)
+    }
+
+    fn api_error(code: NumericOrTextCode) -> ClientError {
+        ClientError::ApiError(ErrorResponse {
+            code,
+            message: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn classifies_transport_errors_as_node_failures() {
+        let connection_error = Client::new()
+            .get("http://127.0.0.1:1")
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(is_node_failure(&connection_error.into()));
+
+        let addr = start_mock_beacon_node(MockBeaconNode::default()).await;
+        let timeout_error = Client::new()
+            .get(format!("http://{addr}/slow"))
+            .timeout(Duration::from_millis(50))
+            .send()
+            .await
+            .unwrap_err();
+
+        assert!(is_node_failure(&timeout_error.into()));
+    }
+
+    #[test]
+    fn classifies_server_errors_as_node_failures() {
+        assert!(is_node_failure(&api_error(NumericOrTextCode::Number(503))));
+        assert!(is_node_failure(&api_error(NumericOrTextCode::String(
+            "500".to_string()
+        ))));
+        assert!(!is_node_failure(&api_error(NumericOrTextCode::Number(404))));
+        assert!(!is_node_failure(&api_error(NumericOrTextCode::String(
+            "BAD_REQUEST".to_string()
+        ))));
+    }
+
+    #[test]
+    fn classifies_unexpected_responses_as_request_failures() {
+        let serde_error = serde_json::from_str::<Value>("<html>").unwrap_err();
+
+        assert!(!is_node_failure(&serde_error.into()));
+        assert!(!is_node_failure(&anyhow::anyhow!("Invalid block").into()));
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_next_node_on_server_errors() {
+        let failing_node = MockBeaconNode {
+            genesis_response: Some((
+                StatusCode::SERVICE_UNAVAILABLE,
+                r#"{ "code": 503, "message": "Node is syncing" }"#,
+            )),
+            ..Default::default()
+        };
+        let healthy_node = MockBeaconNode::default();
+        let client = create_failover_client(&[failing_node.clone(), healthy_node.clone()]).await;
+
+        let genesis = client.get_genesis().await.unwrap().unwrap();
+
+        assert_eq!(genesis.genesis_time, 1606824023);
+
+        // The healthy node is used from then on
+        client.get_genesis().await.unwrap();
+
+        assert_eq!(failing_node.genesis_requests.load(Ordering::Relaxed), 1);
+        assert_eq!(healthy_node.genesis_requests.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_fail_over_on_unexpected_responses() {
+        let invalid_node = MockBeaconNode {
+            genesis_response: Some((StatusCode::OK, "<html>Not a beacon node</html>")),
+            ..Default::default()
+        };
+        let healthy_node = MockBeaconNode::default();
+        let client = create_failover_client(&[invalid_node, healthy_node.clone()]).await;
+
+        let result = client.get_genesis().await;
+
+        assert!(matches!(result, Err(ClientError::SerdeError(_))));
+        assert_eq!(healthy_node.genesis_requests.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn subscribes_to_events_of_next_node_after_failing_over() {
+        let nodes = [MockBeaconNode::default(), MockBeaconNode::default()];
+        let client = create_failover_client(&nodes).await;
+
+        for expected_subscriptions in [[1, 0], [1, 1], [2, 1]] {
+            let mut event_source = client.subscribe_to_events(&[Topic::Head]).unwrap();
+
+            assert!(matches!(event_source.next().await, Some(Ok(Event::Open))));
+
+            event_source.close();
+
+            let subscriptions = nodes
+                .iter()
+                .map(|node| node.event_subscriptions.load(Ordering::Relaxed))
+                .collect::<Vec<_>>();
+
+            assert_eq!(subscriptions, expected_subscriptions);
+            assert!(client.fail_over());
+        }
+    }
+
+    #[test]
+    fn does_not_fail_over_with_a_single_node() {
+        let client =
+            FailoverBeaconClient::new(vec![beacon_client("http://127.0.0.1:1".into())], None);
+
+        assert!(!client.fail_over());
+    }
+}
@@ -16,8 +16,14 @@ use crate::{
     json_get,
 };
 
-use self::types::{Blob, BlobsResponse, Block, BlockId, BlockResponse, Topic};
+use self::types::{
+    Blob, BlobsResponse, Block, BlockId, BlockResponse, DataColumnSidecar,
+    DataColumnSidecarsResponse, Genesis, GenesisResponse, Topic,
+};
+
+pub use self::failover::FailoverBeaconClient;
 
+mod failover;
 pub mod types;
 
 #[derive(Debug, Clone)]
@@ -38,7 +44,16 @@ pub trait CommonBeaconClient: Send + Sync + Debug {
     async fn get_block(&self, block_id: BlockId) -> ClientResult<Option<Block>>;
     async fn get_block_header(&self, block_id: BlockId) -> ClientResult<Option<BlockHeader>>;
     async fn get_blobs(&self, block_id: BlockId) -> ClientResult<Option<Vec<Blob>>>;
+    async fn get_data_column_sidecars(
+        &self,
+        block_id: BlockId,
+    ) -> ClientResult<Option<Vec<DataColumnSidecar>>>;
+    async fn get_genesis(&self) -> ClientResult<Option<Genesis>>;
     fn subscribe_to_events(&self, topics: &[Topic]) -> ClientResult<EventSource>;
+    /// Switches to another beacon node, if any. Returns whether a different node will be used.
+    fn fail_over(&self) -> bool {
+        false
+    }
 }
 
 impl BeaconClient {
@@ -90,6 +105,31 @@ impl CommonBeaconClient for BeaconClient {
         })
     }
 
+    async fn get_data_column_sidecars(
+        &self,
+        block_id: BlockId,
+    ) -> ClientResult<Option<Vec<DataColumnSidecar>>> {
+        let path = format!("v1/debug/beacon/data_column_sidecars/{}", {
+            block_id.to_detailed_string()
+        });
+        let url = self.base_url.join(path.as_str())?;
+
+        json_get!(
+            &self.client,
+            url,
+            DataColumnSidecarsResponse,
+            self.exp_backoff.clone()
+        )
+        .map(|res| res.map(|r| r.data))
+    }
+
+    async fn get_genesis(&self) -> ClientResult<Option<Genesis>> {
+        let url = self.base_url.join("v1/beacon/genesis")?;
+
+        json_get!(&self.client, url, GenesisResponse, self.exp_backoff.clone())
+            .map(|res| res.map(|r| r.data))
+    }
+
     fn subscribe_to_events(&self, topics: &[Topic]) -> ClientResult<EventSource> {
         let topics = topics
             .iter()
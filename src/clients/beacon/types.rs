@@ -61,7 +61,7 @@ pub struct BlockResponse {
     pub data: BlockData,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 pub struct Blob {
     pub kzg_commitment: String,
     pub kzg_proof: String,
@@ -73,6 +73,20 @@ pub struct BlobsResponse {
     pub data: Vec<Blob>,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct DataColumnSidecar {
+    #[serde(deserialize_with = "deserialize_number")]
+    pub index: u32,
+    /// Cells of the column, one per blob of the block.
+    pub column: Vec<Bytes>,
+    pub kzg_commitments: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DataColumnSidecarsResponse {
+    pub data: Vec<DataColumnSidecar>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct BlockHeaderResponse {
     pub data: BlockHeaderData,
@@ -110,6 +124,17 @@ pub struct HeadEventData {
     pub block: B256,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct Genesis {
+    #[serde(deserialize_with = "deserialize_number")]
+    pub genesis_time: u32,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GenesisResponse {
+    pub data: Genesis,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct FinalizedCheckpointEventData {
     pub block: B256,
@@ -15,8 +15,9 @@ use crate::{clients::common::ClientResult, json_get, json_put};
 use self::{
     jwt_manager::{Config as JWTManagerConfig, JWTManager},
     types::{
-        Blob, Block, BlockchainSyncState, BlockchainSyncStateRequest, BlockchainSyncStateResponse,
-        IndexRequest, Transaction,
+        BatchIndexRequest, Blob, Block, BlockchainSyncState, BlockchainSyncStateRequest,
+        BlockchainSyncStateResponse, FailedSlotsChunk, FailedSlotsChunksRequest,
+        FailedSlotsChunksResponse, IndexRequest, RemoveFailedSlotsChunksRequest, Transaction,
     },
 };
 
@@ -36,6 +37,7 @@ pub trait CommonBlobscanClient: Send + Sync + Debug {
         transactions: Vec<Transaction>,
         blobs: Vec<Blob>,
     ) -> ClientResult<()>;
+    async fn index_batch(&self, requests: Vec<IndexRequest>) -> ClientResult<()>;
     async fn get_block(&self, slot: u32) -> ClientResult<Option<BlobscanBlock>>;
     async fn handle_reorg(
         &self,
@@ -44,6 +46,9 @@ pub trait CommonBlobscanClient: Send + Sync + Debug {
     ) -> ClientResult<()>;
     async fn update_sync_state(&self, sync_state: BlockchainSyncState) -> ClientResult<()>;
     async fn get_sync_state(&self) -> ClientResult<Option<BlockchainSyncState>>;
+    async fn add_failed_slots_chunks(&self, chunks: Vec<FailedSlotsChunk>) -> ClientResult<()>;
+    async fn get_failed_slots_chunks(&self) -> ClientResult<Vec<FailedSlotsChunk>>;
+    async fn remove_failed_slots_chunks(&self, chunk_ids: Vec<u32>) -> ClientResult<()>;
 }
 
 #[derive(Debug, Clone)]
@@ -97,6 +102,14 @@ impl CommonBlobscanClient for BlobscanClient {
         json_put!(&self.client, url, token, &req).map(|_: Option<()>| ())
     }
 
+    async fn index_batch(&self, requests: Vec<IndexRequest>) -> ClientResult<()> {
+        let url = self.base_url.join("indexer/block-txs-blobs/batch")?;
+        let token = self.jwt_manager.get_token()?;
+        let req = BatchIndexRequest { blocks: requests };
+
+        json_put!(&self.client, url, token, &req).map(|_: Option<()>| ())
+    }
+
     async fn get_block(&self, slot: u32) -> ClientResult<Option<BlobscanBlock>> {
         let url = self.base_url.join(&format!("slots/{}", slot))?;
 
@@ -135,6 +148,148 @@ impl CommonBlobscanClient for BlobscanClient {
             BlockchainSyncStateResponse,
             self.exp_backoff.clone()
         )
-        .map(|res: Option<BlockchainSyncStateResponse>| Some(res.unwrap().into()))
+        .map(|res| res.map(|r| r.into()))
+    }
+
+    async fn add_failed_slots_chunks(&self, chunks: Vec<FailedSlotsChunk>) -> ClientResult<()> {
+        let url = self.base_url.join("indexer/failed-slots-chunks")?;
+        let token = self.jwt_manager.get_token()?;
+        let req = FailedSlotsChunksRequest { chunks };
+
+        json_put!(&self.client, url, token, &req).map(|_: Option<()>| ())
+    }
+
+    async fn get_failed_slots_chunks(&self) -> ClientResult<Vec<FailedSlotsChunk>> {
+        let url = self.base_url.join("indexer/failed-slots-chunks")?;
+        let token = self.jwt_manager.get_token()?;
+
+        json_get!(
+            &self.client,
+            url,
+            FailedSlotsChunksResponse,
+            token,
+            self.exp_backoff.clone()
+        )
+        .map(|res| res.map(|r| r.chunks).unwrap_or_default())
+    }
+
+    async fn remove_failed_slots_chunks(&self, chunk_ids: Vec<u32>) -> ClientResult<()> {
+        let url = self.base_url.join("indexer/failed-slots-chunks")?;
+        let token = self.jwt_manager.get_token()?;
+        let req = RemoveFailedSlotsChunksRequest { chunk_ids };
+
+        json_put!(DELETE, &self.client, url, token, &req).map(|_: Option<()>| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+
+    use alloy::primitives::U256;
+    use axum::{extract::State, http::StatusCode, routing::put, Json, Router};
+    use serde_json::{json, Value};
+
+    use crate::clients::common::ClientError;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct MockBlobscan {
+        batches: Arc<Mutex<Vec<Vec<u32>>>>,
+        fail: bool,
+    }
+
+    async fn index_batch_handler(
+        State(mock): State<MockBlobscan>,
+        Json(body): Json<BatchIndexRequest>,
+    ) -> (StatusCode, Json<Value>) {
+        let slots = body.blocks.iter().map(|req| req.block.slot).collect();
+
+        mock.batches.lock().unwrap().push(slots);
+
+        if mock.fail {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "code": "BAD_REQUEST", "message": "Invalid batch" })),
+            )
+        } else {
+            (StatusCode::OK, Json(Value::Null))
+        }
+    }
+
+    async fn start_mock_blobscan(mock: MockBlobscan) -> SocketAddr {
+        let app = Router::new()
+            .route("/indexer/block-txs-blobs/batch", put(index_batch_handler))
+            .with_state(mock);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        addr
+    }
+
+    async fn create_client(mock: MockBlobscan) -> BlobscanClient {
+        let addr = start_mock_blobscan(mock).await;
+
+        BlobscanClient::try_with_client(
+            Client::new(),
+            Config {
+                base_url: format!("http://{addr}"),
+                secret_key: "secret".to_string(),
+                exp_backoff: None,
+            },
+        )
+        .unwrap()
+    }
+
+    fn index_request(slot: u32) -> IndexRequest {
+        IndexRequest {
+            block: Block {
+                number: slot as u64,
+                hash: B256::with_last_byte(slot as u8),
+                timestamp: slot as u64 * 12,
+                slot,
+                blob_gas_used: U256::ZERO,
+                excess_blob_gas: U256::ZERO,
+                blob_base_fee: None,
+                fork: None,
+            },
+            transactions: vec![],
+            blobs: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn submits_index_batch_in_order() {
+        let mock = MockBlobscan::default();
+        let client = create_client(mock.clone()).await;
+
+        let result = client
+            .index_batch(vec![index_request(3), index_request(1), index_request(2)])
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(*mock.batches.lock().unwrap(), vec![vec![3, 1, 2]]);
+    }
+
+    #[tokio::test]
+    async fn returns_api_error_of_rejected_batch() {
+        let mock = MockBlobscan {
+            fail: true,
+            ..Default::default()
+        };
+        let client = create_client(mock.clone()).await;
+
+        let result = client
+            .index_batch(vec![index_request(1), index_request(2)])
+            .await;
+
+        assert!(matches!(result, Err(ClientError::ApiError(_))));
+        assert_eq!(*mock.batches.lock().unwrap(), vec![vec![1, 2]]);
     }
 }
@@ -1,14 +1,19 @@
 use core::fmt;
 
 use alloy::primitives::{Address, BlockNumber, BlockTimestamp, Bytes, TxIndex, B256, U256};
-use alloy::rpc::types::{Block as ExecutionBlock, Transaction as ExecutionTransaction};
+use alloy::rpc::types::{
+    Block as ExecutionBlock, Transaction as ExecutionTransaction, TransactionReceipt,
+};
 use anyhow::{Context, Result};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{clients::beacon::types::Blob as BeaconBlob, utils::web3::calculate_versioned_hash};
+use crate::{
+    blob_analysis::BlobMetadata, clients::beacon::types::Blob as BeaconBlob, network::Fork,
+    rollups::TransactionCategory, utils::web3::calculate_versioned_hash,
+};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlobscanBlock {
     pub hash: B256,
     pub number: u32,
@@ -24,6 +29,10 @@ pub struct Block {
     pub slot: u32,
     pub blob_gas_used: U256,
     pub excess_blob_gas: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_base_fee: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork: Option<Fork>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -37,6 +46,20 @@ pub struct Transaction {
     pub index: TxIndex,
     pub gas_price: U256,
     pub max_fee_per_blob_gas: U256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_gas_price: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_price: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<TransactionCategory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollup: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -45,12 +68,17 @@ pub struct Blob {
     pub versioned_hash: B256,
     pub commitment: String,
     pub proof: String,
-    pub data: Bytes,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_reference: Option<String>,
     pub tx_hash: B256,
     pub index: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BlobMetadata>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FailedSlotsChunk {
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -59,6 +87,24 @@ pub struct FailedSlotsChunk {
     pub final_slot: u32,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedSlotsChunksRequest {
+    pub chunks: Vec<FailedSlotsChunk>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedSlotsChunksResponse {
+    pub chunks: Vec<FailedSlotsChunk>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveFailedSlotsChunksRequest {
+    pub chunk_ids: Vec<u32>,
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockchainSyncStateRequest {
@@ -87,7 +133,8 @@ pub struct BlockchainSyncStateResponse {
     pub last_upper_synced_block_slot: Option<u32>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct BlockchainSyncState {
     pub last_finalized_block: Option<u32>,
     pub last_lower_synced_slot: Option<u32>,
@@ -96,13 +143,18 @@ pub struct BlockchainSyncState {
     pub last_upper_synced_block_slot: Option<u32>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct IndexRequest {
     pub block: Block,
     pub transactions: Vec<Transaction>,
     pub blobs: Vec<Blob>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchIndexRequest {
+    pub blocks: Vec<IndexRequest>,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ReorgedBlocksRequestBody {
@@ -114,8 +166,8 @@ impl fmt::Debug for Blob {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Blob {{ versioned_hash: {}, commitment: {}, tx_hash: {}, index: {}, data: [omitted] }}",
-            self.versioned_hash, self.commitment, self.tx_hash, self.index
+            "Blob {{ versioned_hash: {}, commitment: {}, tx_hash: {}, index: {}, data: [omitted], data_reference: {:?} }}",
+            self.versioned_hash, self.commitment, self.tx_hash, self.index, self.data_reference
         )
     }
 }
@@ -130,6 +182,15 @@ impl From<(u32, u32)> for FailedSlotsChunk {
     }
 }
 
+impl fmt::Display for FailedSlotsChunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.id {
+            Some(id) => write!(f, "#{} slots {}-{}", id, self.initial_slot, self.final_slot),
+            None => write!(f, "slots {}-{}", self.initial_slot, self.final_slot),
+        }
+    }
+}
+
 impl<'a> TryFrom<(&'a ExecutionBlock<ExecutionTransaction>, u32)> for Block {
     type Error = anyhow::Error;
 
@@ -167,6 +228,8 @@ impl<'a> TryFrom<(&'a ExecutionBlock<ExecutionTransaction>, u32)> for Block {
             slot,
             blob_gas_used,
             excess_blob_gas,
+            blob_base_fee: None,
+            fork: None,
         })
     }
 }
@@ -175,14 +238,16 @@ impl<'a>
     TryFrom<(
         &'a ExecutionTransaction,
         &'a ExecutionBlock<ExecutionTransaction>,
+        &'a TransactionReceipt,
     )> for Transaction
 {
     type Error = anyhow::Error;
 
     fn try_from(
-        (execution_tx, execution_block): (
+        (execution_tx, execution_block, receipt): (
             &'a ExecutionTransaction,
             &'a ExecutionBlock<ExecutionTransaction>,
+            &'a TransactionReceipt,
         ),
     ) -> Result<Self, Self::Error> {
         let hash = execution_tx.hash;
@@ -221,6 +286,13 @@ impl<'a>
             to,
             gas_price,
             max_fee_per_blob_gas,
+            effective_gas_price: Some(U256::from(receipt.effective_gas_price)),
+            gas_used: Some(U256::from(receipt.gas_used)),
+            blob_gas_price: receipt.blob_gas_price.map(U256::from),
+            blob_gas_used: receipt.blob_gas_used.map(U256::from),
+            status: Some(receipt.status()),
+            category: None,
+            rollup: None,
         })
     }
 }
@@ -236,8 +308,10 @@ impl<'a> TryFrom<(&'a BeaconBlob, u32, B256)> for Blob {
             index,
             commitment: blob_data.kzg_commitment.clone(),
             proof: blob_data.kzg_proof.clone(),
-            data: blob_data.blob.clone(),
+            data: Some(blob_data.blob.clone()),
+            data_reference: None,
             versioned_hash: calculate_versioned_hash(&blob_data.kzg_commitment)?,
+            metadata: None,
         })
     }
 }
@@ -251,8 +325,10 @@ impl<'a> From<(&'a BeaconBlob, &'a B256, usize, &'a B256)> for Blob {
             index: index as u32,
             commitment: blob_data.kzg_commitment.clone(),
             proof: blob_data.kzg_proof.clone(),
-            data: blob_data.blob.clone(),
+            data: Some(blob_data.blob.clone()),
+            data_reference: None,
             versioned_hash: *versioned_hash,
+            metadata: None,
         }
     }
 }
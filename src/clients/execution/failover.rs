@@ -0,0 +1,143 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use alloy::{
+    rpc::json_rpc::{RequestPacket, ResponsePacket},
+    transports::{BoxTransport, TransportError, TransportErrorKind, TransportFut},
+};
+use tower::Service;
+use tracing::warn;
+
+/// Transport that sends requests to one of several execution nodes, failing over to the next one
+/// when a request fails.
+#[derive(Debug, Clone)]
+pub struct FailoverTransport {
+    transports: Arc<Vec<(String, BoxTransport)>>,
+    active_transport: Arc<AtomicUsize>,
+}
+
+impl FailoverTransport {
+    /// Creates a transport from the given node transports, labelled by their URL.
+    pub fn new(transports: Vec<(String, BoxTransport)>) -> Self {
+        Self {
+            transports: Arc::new(transports),
+            active_transport: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Node transports are checked for readiness when called
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        let transports = self.transports.clone();
+        let active_transport = self.active_transport.clone();
+
+        Box::pin(async move {
+            let initial_transport = active_transport.load(Ordering::Relaxed);
+            let mut last_error = None;
+
+            for i in 0..transports.len() {
+                let index = (initial_transport + i) % transports.len();
+                let (url, transport) = &transports[index];
+
+                match transport.clone().call(req.clone()).await {
+                    Ok(response) => {
+                        if index != initial_transport {
+                            active_transport.store(index, Ordering::Relaxed);
+                        }
+
+                        return Ok(response);
+                    }
+                    Err(error) => {
+                        warn!(
+                            node = %url,
+                            ?error,
+                            "Execution node request failed. Failing over to the next node…"
+                        );
+
+                        last_error = Some(error);
+                    }
+                }
+            }
+
+            Err(last_error
+                .unwrap_or_else(|| TransportErrorKind::custom_str("No execution nodes configured")))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        providers::{Provider, ProviderBuilder},
+        rpc::client::RpcClient,
+        transports::Transport,
+    };
+    use serde_json::json;
+
+    use crate::clients::execution::stub::{StubError, StubTransport};
+
+    use super::*;
+
+    fn block_number_node(block_number: u64) -> StubTransport {
+        StubTransport::new(move |_, _| Ok(json!(format!("{block_number:#x}"))))
+    }
+
+    fn failover_provider(nodes: &[&StubTransport]) -> impl Provider<BoxTransport> {
+        let transports = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (format!("node-{i}"), (*node).clone().boxed()))
+            .collect();
+
+        ProviderBuilder::new().on_client(RpcClient::new(
+            FailoverTransport::new(transports).boxed(),
+            true,
+        ))
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_next_node_and_keeps_using_it() {
+        let unreachable_node = StubTransport::unreachable();
+        let healthy_node = block_number_node(10);
+        let provider = failover_provider(&[&unreachable_node, &healthy_node]);
+
+        assert_eq!(provider.get_block_number().await.unwrap(), 10);
+        assert_eq!(provider.get_block_number().await.unwrap(), 10);
+
+        assert_eq!(unreachable_node.requests(), 1);
+        assert_eq!(healthy_node.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_fail_over_on_rpc_errors() {
+        let rejecting_node = StubTransport::new(|_, _| Err(StubError::Rpc("Method not found")));
+        let healthy_node = block_number_node(10);
+        let provider = failover_provider(&[&rejecting_node, &healthy_node]);
+
+        assert!(provider.get_block_number().await.is_err());
+        assert_eq!(healthy_node.requests(), 0);
+    }
+
+    #[tokio::test]
+    async fn fails_when_every_node_fails() {
+        let nodes = [StubTransport::unreachable(), StubTransport::unreachable()];
+        let provider = failover_provider(&[&nodes[0], &nodes[1]]);
+
+        assert!(provider.get_block_number().await.is_err());
+        assert!(nodes.iter().all(|node| node.requests() == 1));
+    }
+}
@@ -0,0 +1,9 @@
+pub use self::{
+    failover::FailoverTransport, quorum::ExecutionQuorum, receipts::get_transaction_receipts,
+};
+
+mod failover;
+mod quorum;
+mod receipts;
+#[cfg(test)]
+mod stub;
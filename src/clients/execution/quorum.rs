@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use alloy::{
+    primitives::B256,
+    providers::Provider,
+    rpc::types::{Block, BlockTransactionsKind, Transaction, TransactionReceipt},
+};
+use anyhow::anyhow;
+use futures::future::join_all;
+use tracing::warn;
+
+use super::get_transaction_receipts;
+
+/// Fetches execution blocks and receipts from several nodes and only accepts them when at least
+/// `threshold` nodes agree on their blob transactions.
+pub struct ExecutionQuorum<T> {
+    providers: Vec<(String, Box<dyn Provider<T>>)>,
+    threshold: usize,
+}
+
+impl<T> ExecutionQuorum<T>
+where
+    T: alloy::transports::Transport + Clone,
+{
+    pub fn new(providers: Vec<(String, Box<dyn Provider<T>>)>, threshold: usize) -> Self {
+        Self {
+            providers,
+            threshold,
+        }
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn total_nodes(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// Returns the block agreed on by the quorum of nodes, or `None` if the quorum agrees it
+    /// doesn't exist.
+    pub async fn get_block(
+        &self,
+        block_hash: B256,
+    ) -> Result<Option<Block<Transaction>>, anyhow::Error> {
+        let responses = join_all(self.providers.iter().map(|(_, provider)| {
+            provider.get_block(block_hash.into(), BlockTransactionsKind::Full)
+        }))
+        .await;
+
+        // Blocks are grouped by their blob transactions, as the block hash is already fixed
+        let mut candidates: Vec<(Option<BlobTransactionsFingerprint>, Option<Block>, usize)> =
+            vec![];
+
+        for ((url, _), response) in self.providers.iter().zip(responses) {
+            let block = match response {
+                Ok(block) => block,
+                Err(error) => {
+                    warn!(node = %url, ?error, %block_hash, "Failed to fetch execution block");
+
+                    continue;
+                }
+            };
+            let fingerprint = block.as_ref().map(BlobTransactionsFingerprint::from);
+
+            match candidates
+                .iter_mut()
+                .find(|(candidate, _, _)| *candidate == fingerprint)
+            {
+                Some((_, _, votes)) => *votes += 1,
+                None => candidates.push((fingerprint, block, 1)),
+            }
+        }
+
+        if candidates.len() > 1 {
+            warn!(
+                %block_hash,
+                variants = candidates.len(),
+                "Execution nodes disagree on the blob transactions of the block"
+            );
+        }
+
+        candidates
+            .into_iter()
+            .find(|(_, _, votes)| *votes >= self.threshold)
+            .map(|(_, block, _)| block)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Execution quorum of {}/{} nodes not reached for block {block_hash}",
+                    self.threshold,
+                    self.providers.len()
+                )
+            })
+    }
+
+    /// Returns the receipts of the given transactions of a block agreed on by the quorum of
+    /// nodes, keyed by transaction hash.
+    pub async fn get_transaction_receipts(
+        &self,
+        block_hash: B256,
+        tx_hashes: &[B256],
+    ) -> Result<HashMap<B256, TransactionReceipt>, anyhow::Error> {
+        let responses = join_all(self.providers.iter().map(|(_, provider)| {
+            get_transaction_receipts(provider.as_ref(), block_hash, tx_hashes)
+        }))
+        .await;
+
+        let mut candidates: Vec<(
+            ReceiptsFingerprint,
+            HashMap<B256, TransactionReceipt>,
+            usize,
+        )> = vec![];
+
+        for ((url, _), response) in self.providers.iter().zip(responses) {
+            let receipts = match response {
+                Ok(receipts) => receipts,
+                Err(error) => {
+                    warn!(node = %url, ?error, %block_hash, "Failed to fetch transaction receipts");
+
+                    continue;
+                }
+            };
+            let fingerprint = ReceiptsFingerprint::from(&receipts);
+
+            match candidates
+                .iter_mut()
+                .find(|(candidate, _, _)| *candidate == fingerprint)
+            {
+                Some((_, _, votes)) => *votes += 1,
+                None => candidates.push((fingerprint, receipts, 1)),
+            }
+        }
+
+        if candidates.len() > 1 {
+            warn!(
+                %block_hash,
+                variants = candidates.len(),
+                "Execution nodes disagree on the transaction receipts of the block"
+            );
+        }
+
+        candidates
+            .into_iter()
+            .find(|(_, _, votes)| *votes >= self.threshold)
+            .map(|(_, receipts, _)| receipts)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Execution quorum of {}/{} nodes not reached for the receipts of block {block_hash}",
+                    self.threshold,
+                    self.providers.len()
+                )
+            })
+    }
+}
+
+/// Blob transaction hashes of a block along with their versioned hashes, sorted by transaction
+/// index.
+#[derive(Debug, PartialEq)]
+struct BlobTransactionsFingerprint(Vec<(B256, Vec<B256>)>);
+
+impl From<&Block<Transaction>> for BlobTransactionsFingerprint {
+    fn from(block: &Block<Transaction>) -> Self {
+        let mut blob_transactions = block
+            .transactions
+            .txns()
+            .filter_map(|tx| {
+                tx.blob_versioned_hashes.as_ref().map(|versioned_hashes| {
+                    (tx.transaction_index, tx.hash, versioned_hashes.clone())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        blob_transactions.sort_by_key(|(index, _, _)| *index);
+
+        Self(
+            blob_transactions
+                .into_iter()
+                .map(|(_, hash, versioned_hashes)| (hash, versioned_hashes))
+                .collect(),
+        )
+    }
+}
+
+/// Receipt fields indexed along with blob transactions.
+#[derive(Debug, PartialEq)]
+struct ReceiptFields {
+    tx_hash: B256,
+    status: bool,
+    gas_used: u128,
+    effective_gas_price: u128,
+    blob_gas_used: Option<u128>,
+    blob_gas_price: Option<u128>,
+}
+
+/// Indexed receipt fields of a set of transactions, sorted by transaction hash.
+#[derive(Debug, PartialEq)]
+struct ReceiptsFingerprint(Vec<ReceiptFields>);
+
+impl From<&HashMap<B256, TransactionReceipt>> for ReceiptsFingerprint {
+    fn from(receipts: &HashMap<B256, TransactionReceipt>) -> Self {
+        let mut fields = receipts
+            .values()
+            .map(|receipt| ReceiptFields {
+                tx_hash: receipt.transaction_hash,
+                status: receipt.status(),
+                gas_used: receipt.gas_used,
+                effective_gas_price: receipt.effective_gas_price,
+                blob_gas_used: receipt.blob_gas_used,
+                blob_gas_price: receipt.blob_gas_price,
+            })
+            .collect::<Vec<_>>();
+
+        fields.sort_by_key(|fields| fields.tx_hash);
+
+        Self(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{rpc::types::BlockTransactions, transports::BoxTransport};
+    use serde_json::{json, Value};
+
+    use crate::clients::execution::stub::{receipt, StubTransport};
+
+    use super::*;
+
+    const BLOCK_HASH: B256 = B256::repeat_byte(0xbb);
+
+    /// Block with a single blob transaction carrying the given versioned hash.
+    fn block(versioned_hash: B256) -> Value {
+        let tx = Transaction {
+            hash: B256::repeat_byte(0xaa),
+            block_hash: Some(BLOCK_HASH),
+            transaction_index: Some(0),
+            blob_versioned_hashes: Some(vec![versioned_hash]),
+            ..Default::default()
+        };
+        let block: Block = Block {
+            transactions: BlockTransactions::Full(vec![tx]),
+            ..Default::default()
+        };
+
+        serde_json::to_value(block).unwrap()
+    }
+
+    fn block_node(block: Value) -> StubTransport {
+        StubTransport::new(move |_, _| Ok(block.clone()))
+    }
+
+    fn quorum(nodes: &[StubTransport], threshold: usize) -> ExecutionQuorum<BoxTransport> {
+        let providers = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (format!("node-{i}"), node.provider()))
+            .collect();
+
+        ExecutionQuorum::new(providers, threshold)
+    }
+
+    fn versioned_hashes(block: &Block<Transaction>) -> Vec<B256> {
+        block
+            .transactions
+            .txns()
+            .flat_map(|tx| tx.blob_versioned_hashes.clone().unwrap_or_default())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn returns_block_agreed_on_by_majority() {
+        let agreed_hash = B256::repeat_byte(1);
+        let nodes = [
+            block_node(block(agreed_hash)),
+            block_node(block(B256::repeat_byte(2))),
+            block_node(block(agreed_hash)),
+        ];
+
+        let block = quorum(&nodes, 2).get_block(BLOCK_HASH).await.unwrap();
+
+        assert_eq!(versioned_hashes(&block.unwrap()), vec![agreed_hash]);
+    }
+
+    #[tokio::test]
+    async fn reaches_quorum_despite_unreachable_nodes() {
+        let nodes = [
+            StubTransport::unreachable(),
+            block_node(block(B256::repeat_byte(1))),
+            block_node(block(B256::repeat_byte(1))),
+        ];
+
+        let block = quorum(&nodes, 2).get_block(BLOCK_HASH).await.unwrap();
+
+        assert!(block.is_some());
+    }
+
+    #[tokio::test]
+    async fn returns_no_block_when_quorum_agrees_it_does_not_exist() {
+        let nodes = [
+            block_node(Value::Null),
+            block_node(Value::Null),
+            block_node(block(B256::repeat_byte(1))),
+        ];
+
+        let block = quorum(&nodes, 2).get_block(BLOCK_HASH).await.unwrap();
+
+        assert!(block.is_none());
+    }
+
+    #[tokio::test]
+    async fn fails_on_tied_nodes() {
+        let nodes = [
+            block_node(block(B256::repeat_byte(1))),
+            block_node(block(B256::repeat_byte(1))),
+            block_node(block(B256::repeat_byte(2))),
+            block_node(block(B256::repeat_byte(2))),
+        ];
+
+        let result = quorum(&nodes, 3).get_block(BLOCK_HASH).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn fails_when_every_node_fails() {
+        let nodes = [StubTransport::unreachable(), StubTransport::unreachable()];
+
+        let result = quorum(&nodes, 1).get_block(BLOCK_HASH).await;
+
+        assert!(result.is_err());
+        assert!(nodes.iter().all(|node| node.requests() == 1));
+    }
+
+    #[tokio::test]
+    async fn ignores_blocks_differing_only_in_non_blob_transactions() {
+        let mut block_with_other_tx = block(B256::repeat_byte(1));
+        let other_tx = json!({
+            "hash": B256::repeat_byte(0xcc),
+            "nonce": "0x0",
+            "from": "0x0000000000000000000000000000000000000001",
+            "value": "0x0",
+            "gas": "0x5208",
+            "input": "0x",
+            "transactionIndex": "0x1",
+            "type": "0x0",
+            "gasPrice": "0x1"
+        });
+
+        block_with_other_tx["transactions"]
+            .as_array_mut()
+            .unwrap()
+            .push(other_tx);
+
+        let nodes = [
+            block_node(block(B256::repeat_byte(1))),
+            block_node(block_with_other_tx),
+        ];
+
+        let block = quorum(&nodes, 2).get_block(BLOCK_HASH).await.unwrap();
+
+        assert!(block.is_some());
+    }
+
+    /// Node serving block receipts of a blob transaction, with the given gas used.
+    fn receipts_node(gas_used: u64) -> StubTransport {
+        StubTransport::new(move |_, _| {
+            Ok(Value::Array(vec![receipt(
+                B256::repeat_byte(0xaa),
+                gas_used,
+            )]))
+        })
+    }
+
+    #[tokio::test]
+    async fn returns_receipts_agreed_on_by_majority() {
+        let nodes = [
+            receipts_node(21_000),
+            receipts_node(42_000),
+            receipts_node(21_000),
+        ];
+        let tx_hash = B256::repeat_byte(0xaa);
+
+        let receipts = quorum(&nodes, 2)
+            .get_transaction_receipts(BLOCK_HASH, &[tx_hash])
+            .await
+            .unwrap();
+
+        assert_eq!(receipts[&tx_hash].gas_used, 21_000);
+    }
+
+    #[tokio::test]
+    async fn fails_on_tied_receipts() {
+        let nodes = [receipts_node(21_000), receipts_node(42_000)];
+
+        let result = quorum(&nodes, 2)
+            .get_transaction_receipts(BLOCK_HASH, &[B256::repeat_byte(0xaa)])
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn fails_when_every_node_fails_to_return_receipts() {
+        let nodes = [StubTransport::unreachable(), StubTransport::unreachable()];
+
+        let result = quorum(&nodes, 1)
+            .get_transaction_receipts(BLOCK_HASH, &[B256::repeat_byte(0xaa)])
+            .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use alloy::{
+    primitives::B256, providers::Provider, rpc::types::TransactionReceipt, transports::Transport,
+};
+use anyhow::Context;
+use futures::future::try_join_all;
+use tracing::debug;
+
+/// Returns the receipts of the given transactions of a block, keyed by transaction hash.
+///
+/// Receipts are fetched at once with `eth_getBlockReceipts`, falling back to a
+/// `eth_getTransactionReceipt` call per transaction for nodes that don't support it.
+pub async fn get_transaction_receipts<T>(
+    provider: &dyn Provider<T>,
+    block_hash: B256,
+    tx_hashes: &[B256],
+) -> Result<HashMap<B256, TransactionReceipt>, anyhow::Error>
+where
+    T: Transport + Clone,
+{
+    match provider.get_block_receipts(block_hash.into()).await {
+        Ok(Some(receipts)) => {
+            return Ok(receipts
+                .into_iter()
+                .filter(|receipt| tx_hashes.contains(&receipt.transaction_hash))
+                .map(|receipt| (receipt.transaction_hash, receipt))
+                .collect())
+        }
+        Ok(None) => debug!(
+            %block_hash,
+            "Block receipts not found. Fetching them by transaction"
+        ),
+        Err(error) => debug!(
+            %block_hash,
+            ?error,
+            "Failed to fetch block receipts. Fetching them by transaction"
+        ),
+    }
+
+    let receipts = try_join_all(tx_hashes.iter().map(|tx_hash| async move {
+        provider
+            .get_transaction_receipt(*tx_hash)
+            .await?
+            .with_context(|| format!("Receipt not found for tx {tx_hash}"))
+    }))
+    .await?;
+
+    Ok(receipts
+        .into_iter()
+        .map(|receipt| (receipt.transaction_hash, receipt))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::clients::execution::stub::{receipt, StubError, StubTransport};
+
+    use super::*;
+
+    const BLOCK_HASH: B256 = B256::repeat_byte(0xbb);
+
+    fn tx_hash(byte: u8) -> B256 {
+        B256::repeat_byte(byte)
+    }
+
+    /// Node serving the receipts of the given transactions, by transaction only unless block
+    /// receipts are supported.
+    fn receipts_node(tx_hashes: Vec<B256>, supports_block_receipts: bool) -> StubTransport {
+        StubTransport::new(move |method, params| match method {
+            "eth_getBlockReceipts" if supports_block_receipts => Ok(Value::Array(
+                tx_hashes
+                    .iter()
+                    .map(|hash| receipt(*hash, 21_000))
+                    .collect(),
+            )),
+            "eth_getBlockReceipts" => Err(StubError::Rpc("Method not found")),
+            "eth_getTransactionReceipt" => {
+                let requested_hash: B256 = serde_json::from_value(params[0].clone()).unwrap();
+
+                Ok(if tx_hashes.contains(&requested_hash) {
+                    receipt(requested_hash, 21_000)
+                } else {
+                    json!(null)
+                })
+            }
+            _ => unimplemented!("Unexpected {method} request"),
+        })
+    }
+
+    #[tokio::test]
+    async fn returns_requested_receipts_of_block() {
+        let node = receipts_node(vec![tx_hash(1), tx_hash(2), tx_hash(3)], true);
+
+        let receipts = get_transaction_receipts(
+            node.provider().as_ref(),
+            BLOCK_HASH,
+            &[tx_hash(1), tx_hash(3)],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(receipts.len(), 2);
+        assert!(receipts.contains_key(&tx_hash(1)));
+        assert!(receipts.contains_key(&tx_hash(3)));
+        assert_eq!(node.requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn fetches_receipts_by_transaction_without_block_receipts_support() {
+        let node = receipts_node(vec![tx_hash(1), tx_hash(2)], false);
+
+        let receipts = get_transaction_receipts(
+            node.provider().as_ref(),
+            BLOCK_HASH,
+            &[tx_hash(1), tx_hash(2)],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(receipts[&tx_hash(2)].transaction_hash, tx_hash(2));
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(node.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn fails_when_a_transaction_receipt_is_missing() {
+        let node = receipts_node(vec![tx_hash(1)], false);
+
+        let error = get_transaction_receipts(
+            node.provider().as_ref(),
+            BLOCK_HASH,
+            &[tx_hash(1), tx_hash(2)],
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            format!("Receipt not found for tx {}", tx_hash(2))
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use alloy::{
+    primitives::B256,
+    providers::{Provider, ProviderBuilder},
+    rpc::{
+        client::RpcClient,
+        json_rpc::{ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload},
+    },
+    transports::{BoxTransport, Transport, TransportError, TransportErrorKind, TransportFut},
+};
+use serde_json::{json, Value};
+use tower::Service;
+
+type Handler = dyn Fn(&str, Value) -> Result<Value, StubError> + Send + Sync;
+
+/// Failure of a stubbed request.
+#[derive(Debug, Clone)]
+pub enum StubError {
+    /// The node couldn't be reached.
+    Transport,
+    /// The node returned a JSON-RPC error, e.g. for unsupported methods.
+    Rpc(&'static str),
+}
+
+/// Execution node transport answering JSON-RPC requests with the result the handler gives for
+/// their method and params, counting the requests received.
+#[derive(Clone)]
+pub struct StubTransport {
+    handler: Arc<Handler>,
+    requests: Arc<AtomicUsize>,
+}
+
+impl StubTransport {
+    pub fn new(
+        handler: impl Fn(&str, Value) -> Result<Value, StubError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            requests: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Transport of a node that can't be reached.
+    pub fn unreachable() -> Self {
+        Self::new(|_, _| Err(StubError::Transport))
+    }
+
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    pub fn provider(&self) -> Box<dyn Provider<BoxTransport>> {
+        Box::new(ProviderBuilder::new().on_client(RpcClient::new(self.clone().boxed(), true)))
+    }
+}
+
+impl Service<RequestPacket> for StubTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+
+        let RequestPacket::Single(req) = req else {
+            unimplemented!("Batch requests aren't stubbed")
+        };
+        let params = req
+            .params()
+            .map(|params| serde_json::from_str(params.get()).unwrap())
+            .unwrap_or(Value::Null);
+        let payload = match (self.handler)(req.method(), params) {
+            Ok(result) => {
+                ResponsePayload::Success(serde_json::value::to_raw_value(&result).unwrap())
+            }
+            Err(StubError::Transport) => {
+                return Box::pin(async {
+                    Err(TransportErrorKind::custom_str("Connection refused"))
+                })
+            }
+            Err(StubError::Rpc(message)) => ResponsePayload::Failure(ErrorPayload {
+                code: -32601,
+                message: message.into(),
+                data: None,
+            }),
+        };
+        let response = Response {
+            id: req.id().clone(),
+            payload,
+        };
+
+        Box::pin(async move { Ok(ResponsePacket::Single(response)) })
+    }
+}
+
+/// Receipt of a successful blob transaction of block 1, as returned by execution nodes.
+pub fn receipt(tx_hash: B256, gas_used: u64) -> Value {
+    json!({
+        "type": "0x3",
+        "status": "0x1",
+        "cumulativeGasUsed": format!("{gas_used:#x}"),
+        "logs": [],
+        "logsBloom": format!("0x{}", "00".repeat(256)),
+        "transactionHash": tx_hash,
+        "transactionIndex": "0x0",
+        "blockHash": B256::repeat_byte(0xbb),
+        "blockNumber": "0x1",
+        "gasUsed": format!("{gas_used:#x}"),
+        "effectiveGasPrice": "0x1",
+        "blobGasUsed": "0x20000",
+        "blobGasPrice": "0x1",
+        "from": "0x0000000000000000000000000000000000000001",
+        "to": "0x0000000000000000000000000000000000000002",
+        "contractAddress": null
+    })
+}
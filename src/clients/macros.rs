@@ -1,119 +1,136 @@
 #[macro_export]
-/// Make a GET request sending and expecting JSON.
+/// Make a GET request sending and expecting JSON, evaluating to `Ok(None)` on a 404.
 /// if JSON deser fails, emit a `WARN` level tracing event
 macro_rules! json_get {
     ($client:expr, $url:expr, $expected:ty, $exp_backoff:expr) => {
         json_get!($client, $url, $expected, "", $exp_backoff)
     };
     ($client:expr, $url:expr, $expected:ty, $auth_token:expr, $exp_backoff: expr) => {{
-        let url = $url.clone();
+        // Evaluated in its own block so the early returns only end the request
+        async {
+            let url = $url.clone();
+            let endpoint = $crate::metrics::endpoint_label(&url);
+            let _timer = $crate::metrics::HTTP_REQUEST_DURATION
+                .with_label_values(&["GET", endpoint.as_str()])
+                .start_timer();
 
-        tracing::trace!(method = "GET", url = url.as_str(), "Dispatching API request");
+            tracing::trace!(method = "GET", url = url.as_str(), "Dispatching API request");
 
-        let mut req = $client.get($url);
+            let mut req = $client.get($url);
 
-        if !$auth_token.is_empty() {
-          req = req.bearer_auth($auth_token);
-        }
+            if !$auth_token.is_empty() {
+              req = req.bearer_auth($auth_token);
+            }
 
-        let resp = if $exp_backoff.is_some() {
-            match backoff::future::retry_notify(
-                $exp_backoff.unwrap(),
-                || {
-                    let req = req.try_clone().unwrap();
+            let resp = if $exp_backoff.is_some() {
+                match backoff::future::retry_notify(
+                    $exp_backoff.unwrap(),
+                    || {
+                        let req = req.try_clone().unwrap();
+
+                        async move { req.send().await.map_err(|err| err.into()) }
+                    },
+                    |error, duration: std::time::Duration| {
+                        let duration = duration.as_secs();
+
+                        $crate::metrics::HTTP_REQUEST_RETRIES
+                            .with_label_values(&["GET", endpoint.as_str()])
+                            .inc();
+
+                        tracing::warn!(
+                            method = "GET",
+                            url = %url,
+                            ?error,
+                            "Failed to send request. Retrying in {duration} seconds…"
+                        );
+                    },
+                )
+                .await {
+                    Ok(resp) => resp,
+                    Err(error) => {
+                        tracing::warn!(
+                            method = "GET",
+                            url = %url,
+                            ?error,
+                            "Failed to send request. All retries failed"
+                        );
+
+                        return Err(error.into())
+                    }
+                }
+            } else {
+                match req.send().await {
+                    Err(error) => {
+                        tracing::warn!(
+                            method = "GET",
+                            url = %url,
+                            ?error,
+                            "Failed to send request"
+                        );
+
+                        return Err(error.into())
+                    },
+                    Ok(resp) => resp
+                }
+            };
 
-                    async move { req.send().await.map_err(|err| err.into()) }
-                },
-                |error, duration: std::time::Duration| {
-                    let duration = duration.as_secs();
+            let status = resp.status();
 
-                    tracing::warn!(
-                        method = "GET",
-                        url = %url,
-                        ?error,
-                        "Failed to send request. Retrying in {duration} seconds…"
-                    );
-                },
-            )
-            .await {
-                Ok(resp) => resp,
-                Err(error) => {
-                    tracing::warn!(
-                        method = "GET",
-                        url = %url,
-                        ?error,
-                        "Failed to send request. All retries failed"
-                    );
+            if status.as_u16() == 404 {
+              return Ok(None)
+            };
 
-                    return Err(error.into())
-                }
-            }
-        } else {
-            match req.send().await {
-                Err(error) => {
+            let text = resp.text().await?;
+            let result: Result<$crate::clients::common::ClientResponse<$expected>, _> = serde_json::from_str(&text);
+
+            match result {
+                Err(e) => {
                     tracing::warn!(
                         method = "GET",
                         url = %url,
-                        ?error,
-                        "Failed to send request"
+                        response = text.as_str(),
+                        "Unexpected response from server"
                     );
 
-                    return Err(error.into())
+                    Err(e.into())
                 },
-                Ok(resp) => resp
-            }
-        };
-
-        let status = resp.status();
-
-        if status.as_u16() == 404 {
-          return Ok(None)
-        };
-
-        let text = resp.text().await?;
-        let result: Result<$crate::clients::common::ClientResponse<$expected>, _> = serde_json::from_str(&text);
-
-        match result {
-            Err(e) => {
-                tracing::warn!(
-                    method = "GET",
-                    url = %url,
-                    response = text.as_str(),
-                    "Unexpected response from server"
-                );
-
-                Err(e.into())
-            },
-            Ok(response) => {
-              response.into_client_result()
+                Ok(response) => {
+                  response.into_client_result()
+                }
             }
         }
+        .await
     }};
 }
 
 #[macro_export]
-/// Make a PUT request sending JSON.
+/// Make a request with a JSON body, using the PUT method unless another one is given.
 /// if JSON deser fails, emit a `WARN` level tracing event
 macro_rules! json_put {
-    ($client:expr, $url:expr, $auth_token:expr, $body:expr) => {
-        json_put!($client, $url, (), $auth_token, $body)
+    ($method:ident, $client:expr, $url:expr, $auth_token:expr, $body:expr) => {
+        json_put!($method, $client, $url, (), $auth_token, $body)
     };
-    ($client:expr, $url:expr, $expected:ty, $auth_token:expr, $body:expr) => {{
+    ($method:ident, $client:expr, $url:expr, $expected:ty, $auth_token:expr, $body:expr) => {{
+        let method = stringify!($method);
         let url = $url.clone();
         let body = format!("{:?}", $body);
+        let endpoint = $crate::metrics::endpoint_label(&url);
+        let _timer = $crate::metrics::HTTP_REQUEST_DURATION
+            .with_label_values(&[method, endpoint.as_str()])
+            .start_timer();
 
-        tracing::trace!(method = "PUT", url = url.as_str(), body, "Dispatching API client request");
+        tracing::trace!(method, url = url.as_str(), body, "Dispatching API client request");
 
 
         let resp = match $client
-            .put($url)
+            .request(reqwest::Method::$method, $url)
             .bearer_auth($auth_token)
             .json($body)
             .send()
             .await {
                 Err(error) => {
                     tracing::warn!(
-                        method = "PUT",
+                        method,
                         url = %url,
                         body = body,
                         ?error,
@@ -130,7 +147,7 @@ macro_rules! json_put {
 
         if result.is_err() {
             tracing::warn!(
-                method = "PUT",
+                method,
                 url = %url,
                 body,
                 response = text.as_str(),
@@ -140,4 +157,10 @@ macro_rules! json_put {
 
         result.into_client_result()
     }};
+    ($client:expr, $url:expr, $auth_token:expr, $body:expr) => {
+        json_put!(PUT, $client, $url, (), $auth_token, $body)
+    };
+    ($client:expr, $url:expr, $expected:ty, $auth_token:expr, $body:expr) => {
+        json_put!(PUT, $client, $url, $expected, $auth_token, $body)
+    };
 }
@@ -1,44 +1,98 @@
-use std::{sync::Arc, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
 
 use alloy::{
     providers::{Provider, ProviderBuilder},
-    transports::http::ReqwestTransport,
+    rpc::client::{BuiltInConnectionString, RpcClient},
+    transports::{
+        http::{self, Http},
+        BoxTransport, Transport,
+    },
 };
-use anyhow::Result as AnyhowResult;
+use anyhow::{Context as AnyhowContext, Result as AnyhowResult};
 use backoff::ExponentialBackoffBuilder;
+use c_kzg::{ethereum_kzg_settings_arc, KzgSettings};
 use dyn_clone::DynClone;
+use futures::future::try_join_all;
 
 use crate::{
+    blob_storage::{BlobStorage, BlobStorageType, S3Config as BlobStorageS3Config},
     clients::{
-        beacon::{BeaconClient, CommonBeaconClient, Config as BeaconClientConfig},
+        beacon::{
+            BeaconClient, CommonBeaconClient, Config as BeaconClientConfig, FailoverBeaconClient,
+        },
         blobscan::{BlobscanClient, CommonBlobscanClient, Config as BlobscanClientConfig},
+        execution::{ExecutionQuorum, FailoverTransport},
     },
     env::Environment,
+    journal::Journal,
+    network::{BlobSchedule, ForkSchedule, Network},
+    rollups::RollupRegistry,
+    sinks::{DryRunSink, FileSink, IndexBatchConfig, PostgresSink, Sink, SinkType},
 };
 
+#[cfg(test)]
+use crate::{clients::beacon::MockCommonBeaconClient, sinks::MockSink};
 // #[cfg(test)]
 // use crate::clients::{beacon::MockCommonBeaconClient, blobscan::MockCommonBlobscanClient};
 
 pub trait CommonContext<T>: Send + Sync + DynClone {
     fn beacon_client(&self) -> &dyn CommonBeaconClient;
-    fn blobscan_client(&self) -> &dyn CommonBlobscanClient;
+    fn secondary_beacon_client(&self) -> Option<&dyn CommonBeaconClient>;
+    fn sink(&self) -> &dyn Sink;
     fn provider(&self) -> &dyn Provider<T>;
+    fn execution_quorum(&self) -> Option<&ExecutionQuorum<T>>;
+    fn kzg_settings(&self) -> &Arc<KzgSettings>;
+    fn blob_storage(&self) -> Option<&BlobStorage>;
+    fn rollup_registry(&self) -> &RollupRegistry;
+    fn blob_analysis_enabled(&self) -> bool;
+    fn fork_schedule(&self) -> &ForkSchedule;
+    fn blob_schedule(&self) -> &BlobSchedule;
+    fn journal(&self) -> Option<&Journal>;
+    fn index_batch_config(&self) -> Option<&IndexBatchConfig>;
 }
 
-dyn_clone::clone_trait_object!(CommonContext<ReqwestTransport>);
+dyn_clone::clone_trait_object!(<T> CommonContext<T>);
 // dyn_clone::clone_trait_object!(CommonContext<MockProvider>);
 
 pub struct Config {
     pub blobscan_api_endpoint: String,
-    pub beacon_node_url: String,
-    pub execution_node_endpoint: String,
+    pub beacon_node_urls: Vec<String>,
+    pub secondary_beacon_node_url: Option<String>,
+    pub execution_node_urls: Vec<String>,
+    pub execution_node_quorum: Option<usize>,
+    pub sink: SinkType,
+    pub sink_file_dir: Option<String>,
+    pub database_url: Option<String>,
     pub secret_key: String,
+    pub kzg_trusted_setup_path: Option<String>,
+    pub network: Network,
+    pub rollup_registry_path: Option<String>,
+    pub blob_analysis: bool,
+    pub fork_schedule: ForkSchedule,
+    pub blob_schedule: BlobSchedule,
+    pub journal_path: Option<String>,
+    pub blob_storage: Option<BlobStorageType>,
+    pub blob_storage_dir: Option<String>,
+    pub blob_storage_s3: Option<BlobStorageS3Config>,
+    pub dry_run: bool,
+    pub dry_run_output: Option<String>,
+    pub index_batch: Option<IndexBatchConfig>,
 }
 
 struct ContextRef<T> {
     pub beacon_client: Box<dyn CommonBeaconClient>,
-    pub blobscan_client: Box<dyn CommonBlobscanClient>,
+    pub secondary_beacon_client: Option<Box<dyn CommonBeaconClient>>,
+    pub sink: Box<dyn Sink>,
     pub provider: Box<dyn Provider<T>>,
+    pub execution_quorum: Option<ExecutionQuorum<T>>,
+    pub kzg_settings: Arc<KzgSettings>,
+    pub blob_storage: Option<BlobStorage>,
+    pub rollup_registry: RollupRegistry,
+    pub blob_analysis: bool,
+    pub fork_schedule: ForkSchedule,
+    pub blob_schedule: BlobSchedule,
+    pub journal: Option<Journal>,
+    pub index_batch: Option<IndexBatchConfig>,
 }
 
 #[derive(Clone)]
@@ -46,71 +100,433 @@ pub struct Context<T> {
     inner: Arc<ContextRef<T>>,
 }
 
-impl Context<ReqwestTransport> {
-    pub fn try_new(config: Config) -> AnyhowResult<Self> {
+impl Context<BoxTransport> {
+    pub async fn try_new(config: Config) -> AnyhowResult<Self> {
         let Config {
             blobscan_api_endpoint,
-            beacon_node_url,
-            execution_node_endpoint,
+            beacon_node_urls,
+            secondary_beacon_node_url,
+            execution_node_urls,
+            execution_node_quorum,
+            sink,
+            sink_file_dir,
+            database_url,
             secret_key,
+            kzg_trusted_setup_path,
+            network,
+            rollup_registry_path,
+            blob_storage,
+            blob_storage_dir,
+            blob_storage_s3,
+            dry_run,
+            dry_run_output,
+            index_batch,
+            blob_analysis,
+            fork_schedule,
+            blob_schedule,
+            journal_path,
         } = config;
         let exp_backoff = Some(ExponentialBackoffBuilder::default().build());
+        let kzg_settings = match kzg_trusted_setup_path {
+            Some(path) => Arc::new(
+                KzgSettings::load_trusted_setup_file(Path::new(&path))
+                    .map_err(|err| anyhow::anyhow!("{err:?}"))
+                    .with_context(|| format!("Failed to load KZG trusted setup from {path}"))?,
+            ),
+            None => ethereum_kzg_settings_arc(),
+        };
 
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(8))
             .build()?;
 
+        let sink: Box<dyn Sink> = match sink {
+            SinkType::Blobscan => Box::new(BlobscanClient::try_with_client(
+                client.clone(),
+                BlobscanClientConfig {
+                    base_url: blobscan_api_endpoint,
+                    secret_key,
+                    exp_backoff: exp_backoff.clone(),
+                },
+            )?),
+            SinkType::File => {
+                let dir = sink_file_dir
+                    .with_context(|| "A directory is required when using the file sink")?;
+
+                if dry_run {
+                    Box::new(FileSink::try_open(Path::new(&dir))?)
+                } else {
+                    Box::new(FileSink::try_new(Path::new(&dir))?)
+                }
+            }
+            SinkType::Postgres => {
+                let database_url = database_url
+                    .with_context(|| "A database URL is required when using the postgres sink")?;
+
+                if dry_run {
+                    Box::new(PostgresSink::try_connect(&database_url).await?)
+                } else {
+                    Box::new(PostgresSink::try_new(&database_url).await?)
+                }
+            }
+        };
+
+        let sink: Box<dyn Sink> = if dry_run {
+            Box::new(DryRunSink::try_new(
+                sink,
+                dry_run_output.as_deref().map(Path::new),
+            )?)
+        } else {
+            sink
+        };
+
+        let secondary_beacon_client = match secondary_beacon_node_url {
+            Some(base_url) => {
+                let beacon_client: Box<dyn CommonBeaconClient> =
+                    Box::new(BeaconClient::try_with_client(
+                        client.clone(),
+                        BeaconClientConfig {
+                            base_url,
+                            exp_backoff: exp_backoff.clone(),
+                        },
+                    )?);
+
+                Some(beacon_client)
+            }
+            None => None,
+        };
+
+        let beacon_client: Box<dyn CommonBeaconClient> = if beacon_node_urls.len() > 1 {
+            // Failed requests are retried by the failover client once every node has failed
+            let beacon_clients = beacon_node_urls
+                .into_iter()
+                .map(|base_url| {
+                    BeaconClient::try_with_client(
+                        client.clone(),
+                        BeaconClientConfig {
+                            base_url,
+                            exp_backoff: None,
+                        },
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Box::new(FailoverBeaconClient::new(beacon_clients, exp_backoff))
+        } else {
+            let base_url = beacon_node_urls
+                .into_iter()
+                .next()
+                .with_context(|| "A beacon node endpoint is required")?;
+
+            Box::new(BeaconClient::try_with_client(
+                client,
+                BeaconClientConfig {
+                    base_url,
+                    exp_backoff,
+                },
+            )?)
+        };
+
+        // The execution nodes client comes from the reqwest version used by alloy
+        let execution_client = http::reqwest::Client::builder()
+            .timeout(Duration::from_secs(8))
+            .build()?;
+        let execution_transports = try_join_all(execution_node_urls.into_iter().map(|url| {
+            let execution_client = execution_client.clone();
+
+            async move {
+                connect_execution_node(execution_client, &url)
+                    .await
+                    .map(|transport| (url, transport))
+            }
+        }))
+        .await?;
+        let provider_transport = match execution_transports.as_slice() {
+            [] => anyhow::bail!("An execution node endpoint is required"),
+            [(_, transport)] => transport.clone(),
+            transports => FailoverTransport::new(transports.to_vec()).boxed(),
+        };
+        let provider: Box<dyn Provider<BoxTransport>> =
+            Box::new(ProviderBuilder::new().on_client(RpcClient::new(provider_transport, false)));
+        let execution_quorum = execution_node_quorum.map(|threshold| {
+            let providers = execution_transports
+                .into_iter()
+                .map(|(url, transport)| {
+                    let provider: Box<dyn Provider<BoxTransport>> = Box::new(
+                        ProviderBuilder::new().on_client(RpcClient::new(transport, false)),
+                    );
+
+                    (url, provider)
+                })
+                .collect();
+
+            ExecutionQuorum::new(providers, threshold)
+        });
+
+        let blob_storage = match blob_storage {
+            // Blob data isn't stored anywhere during dry runs
+            _ if dry_run => None,
+            Some(BlobStorageType::Filesystem) => {
+                let dir = blob_storage_dir.with_context(|| {
+                    "A directory is required when using the filesystem blob storage"
+                })?;
+
+                Some(BlobStorage::try_new_filesystem(Path::new(&dir))?)
+            }
+            Some(BlobStorageType::S3) => {
+                let s3_config = blob_storage_s3
+                    .with_context(|| "A bucket is required when using the S3 blob storage")?;
+
+                Some(BlobStorage::try_new_s3(s3_config)?)
+            }
+            None => None,
+        };
+
+        let journal = match journal_path {
+            // Nothing is indexed during dry runs, so there's no progress to record
+            _ if dry_run => None,
+            Some(path) => Some(Journal::try_new(Path::new(&path))?),
+            None => None,
+        };
+
+        let rollup_registry =
+            RollupRegistry::try_new(network, rollup_registry_path.as_deref().map(Path::new))?;
+
         Ok(Self {
             inner: Arc::new(ContextRef {
-                blobscan_client: Box::new(BlobscanClient::try_with_client(
-                    client.clone(),
-                    BlobscanClientConfig {
-                        base_url: blobscan_api_endpoint,
-                        secret_key,
-                        exp_backoff: exp_backoff.clone(),
-                    },
-                )?),
-                beacon_client: Box::new(BeaconClient::try_with_client(
-                    client,
-                    BeaconClientConfig {
-                        base_url: beacon_node_url,
-                        exp_backoff,
-                    },
-                )?),
-                // Provider::<HttpProvider>::try_from(execution_node_endpoint)?
-                provider: Box::new(
-                    ProviderBuilder::new().on_http(execution_node_endpoint.parse()?),
-                ),
+                sink,
+                beacon_client,
+                secondary_beacon_client,
+                provider,
+                execution_quorum,
+                kzg_settings,
+                blob_storage,
+                rollup_registry,
+                blob_analysis,
+                fork_schedule,
+                blob_schedule,
+                journal,
+                index_batch,
             }),
         })
     }
 }
 
-impl CommonContext<ReqwestTransport> for Context<ReqwestTransport> {
+/// Connects to an execution node using the transport matching its endpoint: HTTP(S) and
+/// WebSocket URLs, or an IPC socket path.
+async fn connect_execution_node(
+    client: http::reqwest::Client,
+    endpoint: &str,
+) -> AnyhowResult<BoxTransport> {
+    let connection = endpoint
+        .parse::<BuiltInConnectionString>()
+        .with_context(|| format!("Invalid execution node endpoint {endpoint}"))?;
+
+    let transport = match connection {
+        BuiltInConnectionString::Http(url) => Http::with_client(client, url).boxed(),
+        connection => connection
+            .connect_boxed()
+            .await
+            .with_context(|| format!("Failed to connect to execution node {endpoint}"))?,
+    };
+
+    Ok(transport)
+}
+
+impl<T> CommonContext<T> for Context<T>
+where
+    T: Transport + Clone + Send + Sync + 'static,
+{
     fn beacon_client(&self) -> &dyn CommonBeaconClient {
         self.inner.beacon_client.as_ref()
     }
 
-    fn blobscan_client(&self) -> &dyn CommonBlobscanClient {
-        self.inner.blobscan_client.as_ref()
+    fn secondary_beacon_client(&self) -> Option<&dyn CommonBeaconClient> {
+        self.inner.secondary_beacon_client.as_deref()
+    }
+
+    fn sink(&self) -> &dyn Sink {
+        self.inner.sink.as_ref()
     }
 
-    fn provider(&self) -> &dyn Provider<ReqwestTransport> {
+    fn provider(&self) -> &dyn Provider<T> {
         self.inner.provider.as_ref()
     }
+
+    fn execution_quorum(&self) -> Option<&ExecutionQuorum<T>> {
+        self.inner.execution_quorum.as_ref()
+    }
+
+    fn kzg_settings(&self) -> &Arc<KzgSettings> {
+        &self.inner.kzg_settings
+    }
+
+    fn blob_storage(&self) -> Option<&BlobStorage> {
+        self.inner.blob_storage.as_ref()
+    }
+
+    fn rollup_registry(&self) -> &RollupRegistry {
+        &self.inner.rollup_registry
+    }
+
+    fn blob_analysis_enabled(&self) -> bool {
+        self.inner.blob_analysis
+    }
+
+    fn fork_schedule(&self) -> &ForkSchedule {
+        &self.inner.fork_schedule
+    }
+
+    fn blob_schedule(&self) -> &BlobSchedule {
+        &self.inner.blob_schedule
+    }
+
+    fn journal(&self) -> Option<&Journal> {
+        self.inner.journal.as_ref()
+    }
+
+    fn index_batch_config(&self) -> Option<&IndexBatchConfig> {
+        self.inner.index_batch.as_ref()
+    }
 }
 
 impl From<&Environment> for Config {
     fn from(env: &Environment) -> Self {
         Self {
             blobscan_api_endpoint: env.blobscan_api_endpoint.clone(),
-            beacon_node_url: env.beacon_node_endpoint.clone(),
-            execution_node_endpoint: env.execution_node_endpoint.clone(),
+            beacon_node_urls: env.beacon_node_endpoints(),
+            secondary_beacon_node_url: env.secondary_beacon_node_endpoint.clone(),
+            execution_node_urls: env.execution_node_endpoints(),
+            execution_node_quorum: env.execution_node_quorum,
+            sink: env.sink,
+            sink_file_dir: env.sink_file_dir.clone(),
+            database_url: env.database_url.clone(),
             secret_key: env.secret_key.clone(),
+            kzg_trusted_setup_path: env.kzg_trusted_setup_path.clone(),
+            network: env.network_name.clone(),
+            rollup_registry_path: env.rollup_registry_path.clone(),
+            blob_analysis: env.enable_blob_analysis,
+            fork_schedule: env.fork_schedule(),
+            blob_schedule: env.blob_schedule(),
+            journal_path: env.journal_path.clone(),
+            blob_storage: env.blob_storage,
+            blob_storage_dir: env.blob_storage_dir.clone(),
+            blob_storage_s3: env
+                .blob_storage_s3_bucket
+                .clone()
+                .map(|bucket| BlobStorageS3Config {
+                    bucket,
+                    endpoint: env.blob_storage_s3_endpoint.clone(),
+                    region: env.blob_storage_s3_region.clone(),
+                }),
+            dry_run: false,
+            dry_run_output: None,
+            index_batch: env.index_batch_config(),
         }
     }
 }
 
+/// Context backed by a mocked sink and beacon client, for testing the parts of the indexer
+/// that don't reach the execution node. It uses the devnet's settings, and its provider points
+/// to an unreachable node.
+#[cfg(test)]
+#[derive(Clone)]
+pub struct SinkContext {
+    pub sink: Arc<MockSink>,
+    pub beacon_client: Arc<MockCommonBeaconClient>,
+    pub index_batch: Option<IndexBatchConfig>,
+    provider: Arc<dyn Provider<BoxTransport>>,
+    kzg_settings: Arc<KzgSettings>,
+    rollup_registry: Arc<RollupRegistry>,
+    fork_schedule: ForkSchedule,
+    blob_schedule: BlobSchedule,
+}
+
+#[cfg(test)]
+impl SinkContext {
+    pub fn new(sink: MockSink, index_batch: Option<IndexBatchConfig>) -> Self {
+        let network = Network::Devnet;
+        let fork_schedule = ForkSchedule::new(&network, None, None, None);
+        let blob_schedule = BlobSchedule::new(&network, &fork_schedule, None);
+        let provider_transport = Http::new("http://127.0.0.1:1".parse().unwrap()).boxed();
+
+        Self {
+            sink: Arc::new(sink),
+            beacon_client: Arc::new(MockCommonBeaconClient::new()),
+            index_batch,
+            provider: Arc::new(
+                ProviderBuilder::new().on_client(RpcClient::new(provider_transport, true)),
+            ),
+            kzg_settings: ethereum_kzg_settings_arc(),
+            rollup_registry: Arc::new(RollupRegistry::try_new(network, None).unwrap()),
+            fork_schedule,
+            blob_schedule,
+        }
+    }
+
+    pub fn with_beacon_client(self, beacon_client: MockCommonBeaconClient) -> Self {
+        Self {
+            beacon_client: Arc::new(beacon_client),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+impl CommonContext<BoxTransport> for SinkContext {
+    fn beacon_client(&self) -> &dyn CommonBeaconClient {
+        self.beacon_client.as_ref()
+    }
+
+    fn secondary_beacon_client(&self) -> Option<&dyn CommonBeaconClient> {
+        None
+    }
+
+    fn sink(&self) -> &dyn Sink {
+        self.sink.as_ref()
+    }
+
+    fn provider(&self) -> &dyn Provider<BoxTransport> {
+        self.provider.as_ref()
+    }
+
+    fn execution_quorum(&self) -> Option<&ExecutionQuorum<BoxTransport>> {
+        None
+    }
+
+    fn kzg_settings(&self) -> &Arc<KzgSettings> {
+        &self.kzg_settings
+    }
+
+    fn blob_storage(&self) -> Option<&BlobStorage> {
+        None
+    }
+
+    fn rollup_registry(&self) -> &RollupRegistry {
+        &self.rollup_registry
+    }
+
+    fn blob_analysis_enabled(&self) -> bool {
+        false
+    }
+
+    fn fork_schedule(&self) -> &ForkSchedule {
+        &self.fork_schedule
+    }
+
+    fn blob_schedule(&self) -> &BlobSchedule {
+        &self.blob_schedule
+    }
+
+    fn journal(&self) -> Option<&Journal> {
+        None
+    }
+
+    fn index_batch_config(&self) -> Option<&IndexBatchConfig> {
+        self.index_batch.as_ref()
+    }
+}
+
 // #[cfg(test)]
 // impl Context<MockProvider> {
 //     pub fn new(
@@ -144,3 +560,64 @@ impl From<&Environment> for Config {
 //         &self.inner.provider
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution_client() -> http::reqwest::Client {
+        http::reqwest::Client::new()
+    }
+
+    #[tokio::test]
+    async fn connects_to_http_execution_nodes_lazily() {
+        // HTTP transports only connect when sending requests
+        assert!(
+            connect_execution_node(execution_client(), "http://127.0.0.1:1")
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_to_connect_to_unreachable_websocket_nodes() {
+        let error = connect_execution_node(execution_client(), "ws://127.0.0.1:1")
+            .await
+            .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("Failed to connect to execution node ws://127.0.0.1:1"));
+    }
+
+    #[tokio::test]
+    async fn fails_to_connect_to_paths_not_being_ipc_sockets() {
+        let path = std::env::temp_dir().join("blob-indexer-not-a-socket.ipc");
+
+        std::fs::write(&path, "").unwrap();
+
+        let error = connect_execution_node(execution_client(), &path.to_string_lossy())
+            .await
+            .unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("Failed to connect to execution node"));
+    }
+
+    #[tokio::test]
+    async fn rejects_unsupported_execution_node_endpoints() {
+        let missing_socket = std::env::temp_dir().join("blob-indexer-missing.ipc");
+
+        for endpoint in ["ftp://127.0.0.1", &missing_socket.to_string_lossy()] {
+            let error = connect_execution_node(execution_client(), endpoint)
+                .await
+                .unwrap_err();
+
+            assert_eq!(
+                error.to_string(),
+                format!("Invalid execution node endpoint {endpoint}")
+            );
+        }
+    }
+}
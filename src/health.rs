@@ -0,0 +1,270 @@
+use std::sync::{
+    atomic::{AtomicU64, AtomicU8, Ordering},
+    LazyLock,
+};
+
+use chrono::Utc;
+use serde::Serialize;
+
+pub static HEALTH: LazyLock<Health> = LazyLock::new(Health::default);
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    Disabled,
+    Starting,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Tracks the state of the indexing tasks so it can be reported by the health endpoints.
+#[derive(Debug)]
+pub struct Health {
+    live_task: AtomicU8,
+    historical_task: AtomicU8,
+    live_subscribed_slot: AtomicU64,
+    last_head_slot: AtomicU64,
+    genesis_time: AtomicU64,
+    seconds_per_slot: AtomicU64,
+    max_head_lag: AtomicU64,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub ready: bool,
+    pub live_task: TaskStatus,
+    pub historical_task: TaskStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_head_slot: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_slot: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head_lag: Option<u64>,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            live_task: AtomicU8::new(TaskStatus::Disabled as u8),
+            historical_task: AtomicU8::new(TaskStatus::Disabled as u8),
+            live_subscribed_slot: AtomicU64::new(0),
+            last_head_slot: AtomicU64::new(0),
+            genesis_time: AtomicU64::new(0),
+            seconds_per_slot: AtomicU64::new(12),
+            max_head_lag: AtomicU64::new(5),
+        }
+    }
+}
+
+impl From<u8> for TaskStatus {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => TaskStatus::Starting,
+            2 => TaskStatus::Running,
+            3 => TaskStatus::Completed,
+            4 => TaskStatus::Failed,
+            _ => TaskStatus::Disabled,
+        }
+    }
+}
+
+impl Health {
+    pub fn configure(&self, seconds_per_slot: u64, max_head_lag: u64) {
+        self.seconds_per_slot
+            .store(seconds_per_slot, Ordering::Relaxed);
+        self.max_head_lag.store(max_head_lag, Ordering::Relaxed);
+    }
+
+    pub fn set_genesis_time(&self, genesis_time: u64) {
+        self.genesis_time.store(genesis_time, Ordering::Relaxed);
+    }
+
+    pub fn set_live_task_status(&self, status: TaskStatus) {
+        if status == TaskStatus::Running {
+            if let Some(current_slot) = self.current_slot() {
+                self.live_subscribed_slot
+                    .store(current_slot, Ordering::Relaxed);
+            }
+        }
+
+        self.live_task.store(status as u8, Ordering::Relaxed);
+    }
+
+    pub fn set_historical_task_status(&self, status: TaskStatus) {
+        self.historical_task.store(status as u8, Ordering::Relaxed);
+    }
+
+    pub fn set_last_head_slot(&self, slot: u32) {
+        self.last_head_slot.store(slot.into(), Ordering::Relaxed);
+    }
+
+    /// Returns the slot expected at the current wall-clock time, if the genesis time is known.
+    pub fn current_slot(&self) -> Option<u64> {
+        let genesis_time = self.genesis_time.load(Ordering::Relaxed);
+        let seconds_per_slot = self.seconds_per_slot.load(Ordering::Relaxed);
+        let now = Utc::now().timestamp() as u64;
+
+        if genesis_time == 0 || seconds_per_slot == 0 || now < genesis_time {
+            return None;
+        }
+
+        Some((now - genesis_time) / seconds_per_slot)
+    }
+
+    pub fn report(&self) -> HealthReport {
+        let live_task = TaskStatus::from(self.live_task.load(Ordering::Relaxed));
+        let historical_task = TaskStatus::from(self.historical_task.load(Ordering::Relaxed));
+        let last_head_slot = match self.last_head_slot.load(Ordering::Relaxed) {
+            0 => None,
+            slot => Some(slot),
+        };
+        let current_slot = self.current_slot();
+
+        // Until the first head event arrives, the lag is measured from the slot at which the
+        // stream was subscribed to.
+        let reference_slot = last_head_slot
+            .unwrap_or_default()
+            .max(self.live_subscribed_slot.load(Ordering::Relaxed));
+        let head_lag = match (live_task, current_slot) {
+            (TaskStatus::Running, Some(current_slot)) if reference_slot > 0 => {
+                Some(current_slot.saturating_sub(reference_slot))
+            }
+            _ => None,
+        };
+        let is_lagging =
+            head_lag.is_some_and(|lag| lag > self.max_head_lag.load(Ordering::Relaxed));
+
+        let healthy =
+            live_task != TaskStatus::Failed && historical_task != TaskStatus::Failed && !is_lagging;
+        let has_started =
+            live_task != TaskStatus::Disabled || historical_task != TaskStatus::Disabled;
+        let ready = healthy
+            && has_started
+            && matches!(live_task, TaskStatus::Disabled | TaskStatus::Running);
+
+        HealthReport {
+            healthy,
+            ready,
+            live_task,
+            historical_task,
+            last_head_slot,
+            current_slot,
+            head_lag,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a health tracker whose current slot is `current_slot`.
+    fn health_at_slot(current_slot: u64) -> Health {
+        let health = Health::default();
+
+        health.configure(12, 5);
+        set_current_slot(&health, current_slot);
+
+        health
+    }
+
+    fn set_current_slot(health: &Health, current_slot: u64) {
+        // Half a slot in, so the current slot doesn't change while the test runs
+        health.set_genesis_time(Utc::now().timestamp() as u64 - current_slot * 12 - 6);
+    }
+
+    #[test]
+    fn reports_healthy_when_following_the_head() {
+        let health = health_at_slot(90);
+
+        health.set_historical_task_status(TaskStatus::Completed);
+        health.set_live_task_status(TaskStatus::Running);
+        set_current_slot(&health, 100);
+        health.set_last_head_slot(98);
+
+        let report = health.report();
+
+        assert!(report.healthy);
+        assert!(report.ready);
+        assert_eq!(report.current_slot, Some(100));
+        assert_eq!(report.head_lag, Some(2));
+    }
+
+    #[test]
+    fn reports_not_ready_until_live_task_runs() {
+        let health = health_at_slot(100);
+
+        let report = health.report();
+
+        assert!(report.healthy);
+        assert!(!report.ready);
+
+        health.set_live_task_status(TaskStatus::Starting);
+
+        let report = health.report();
+
+        assert!(report.healthy);
+        assert!(!report.ready);
+    }
+
+    #[test]
+    fn reports_unhealthy_when_head_is_stale() {
+        let health = health_at_slot(88);
+
+        health.set_live_task_status(TaskStatus::Running);
+        health.set_last_head_slot(90);
+        set_current_slot(&health, 100);
+
+        let report = health.report();
+
+        assert!(!report.healthy);
+        assert!(!report.ready);
+        assert_eq!(report.head_lag, Some(10));
+    }
+
+    #[test]
+    fn measures_lag_from_subscription_before_first_head() {
+        let health = health_at_slot(100);
+
+        health.set_live_task_status(TaskStatus::Running);
+
+        let report = health.report();
+
+        assert!(report.healthy);
+        assert_eq!(report.last_head_slot, None);
+        assert_eq!(report.head_lag, Some(0));
+
+        set_current_slot(&health, 110);
+
+        let report = health.report();
+
+        assert!(!report.healthy);
+        assert_eq!(report.head_lag, Some(10));
+    }
+
+    #[test]
+    fn reports_unhealthy_when_a_task_fails() {
+        let health = health_at_slot(100);
+
+        health.set_live_task_status(TaskStatus::Running);
+        health.set_last_head_slot(100);
+        health.set_historical_task_status(TaskStatus::Failed);
+
+        let report = health.report();
+
+        assert!(!report.healthy);
+        assert!(!report.ready);
+
+        health.set_historical_task_status(TaskStatus::Completed);
+        health.set_live_task_status(TaskStatus::Failed);
+
+        let report = health.report();
+
+        assert!(!report.healthy);
+        assert!(!report.ready);
+        assert_eq!(report.head_lag, None);
+    }
+}
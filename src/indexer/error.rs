@@ -15,8 +15,10 @@ pub enum IndexerError {
     CreationFailure(#[source] anyhow::Error),
     #[error(transparent)]
     SyncingTaskError(#[from] IndexingError),
-    #[error("failed to retrieve blobscan's sync state")]
-    BlobscanSyncStateRetrievalError(#[from] ClientError),
+    #[error("failed to retrieve sync state")]
+    SyncStateRetrievalError(#[from] ClientError),
+    #[error("failed to read the local journal")]
+    JournalReadFailure(#[source] anyhow::Error),
     #[error("failed to send syncing task message")]
     SyncingTaskMessageSendFailure(#[from] SendError<IndexerTaskMessage>),
 }
@@ -69,7 +69,7 @@ where
         };
 
         self.context
-            .blobscan_client()
+            .sink()
             .update_sync_state(BlockchainSyncState {
                 last_finalized_block: Some(last_finalized_block_number),
                 last_lower_synced_slot: None,
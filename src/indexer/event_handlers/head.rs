@@ -1,5 +1,6 @@
 use crate::{
     clients::beacon::types::{BlockId, HeadEventData},
+    health::HEALTH,
     synchronizer::{error::SynchronizerError, CommonSynchronizer},
 };
 
@@ -33,6 +34,8 @@ impl HeadEventHandler {
         let head_block_data = serde_json::from_str::<HeadEventData>(&event_data)?;
         let head_slot = head_block_data.slot;
 
+        HEALTH.set_last_head_slot(head_slot);
+
         // If this is the first event being processed, ensure the synchronizer is fully up to date
         if self.is_first_event {
             self.is_first_event = false;
@@ -1,44 +1,78 @@
-use std::thread;
+use std::{thread, time::Duration};
 
-use alloy::{primitives::B256, transports::http::ReqwestTransport};
+use alloy::{
+    primitives::B256,
+    transports::{BoxTransport, Transport},
+};
 use anyhow::anyhow;
+use backoff::{backoff::Backoff, ExponentialBackoffBuilder};
 use event_handlers::{finalized_checkpoint::FinalizedCheckpointHandler, head::HeadEventHandler};
 use futures::StreamExt;
 use reqwest_eventsource::Event;
-use tokio::{sync::mpsc, task::JoinHandle};
-use tracing::{debug, error, info, Instrument};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::mpsc,
+    task::JoinHandle,
+};
+use tracing::{debug, error, info, warn, Instrument};
 
 use crate::{
     args::Args,
-    clients::beacon::types::{BlockHeader, BlockId, Topic},
+    clients::{
+        beacon::types::{BlockHeader, BlockId, Topic},
+        blobscan::types::FailedSlotsChunk,
+    },
     context::{CommonContext, Config as ContextConfig, Context},
     env::Environment,
+    health::{TaskStatus, HEALTH},
     indexer::error::HistoricalIndexingError,
-    synchronizer::{CheckpointType, CommonSynchronizer, SynchronizerBuilder},
+    journal::merge_sync_states,
+    metrics,
+    repairer::{error::RepairerError, RepairSummary, Repairer},
+    retrier::{error::RetrierError, Retrier, RetrySummary},
+    synchronizer::{
+        CheckpointType, CommonSynchronizer, SynchronizerBuilder, DEFAULT_SLOTS_PER_BATCH,
+    },
 };
 
 use self::{
     error::{IndexerError, LiveIndexingError},
-    types::{IndexerResult, IndexerTaskMessage},
+    types::{IndexerResult, IndexerTaskMessage, SyncMode},
 };
 
 pub mod error;
 pub mod event_handlers;
 pub mod types;
 
+/// Delay between checks of the failed slots chunks queue while retries succeed.
+const FAILED_CHUNKS_POLL_INTERVAL: Duration = Duration::from_secs(60);
+const FAILED_CHUNKS_RETRY_MAX_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 pub struct Indexer<T> {
     context: Box<dyn CommonContext<T>>,
     dencun_fork_slot: u32,
-    disable_sync_historical: bool,
+    dry_run: bool,
 
     checkpoint_slots: Option<u32>,
     disabled_checkpoint: Option<CheckpointType>,
     num_threads: u32,
 }
 
-impl Indexer<ReqwestTransport> {
-    pub fn try_new(env: &Environment, args: &Args) -> IndexerResult<Self> {
-        let context = match Context::try_new(ContextConfig::from(env)) {
+impl Indexer<BoxTransport> {
+    #[allow(clippy::result_large_err)]
+    pub async fn try_new(env: &Environment, args: &Args) -> IndexerResult<Self> {
+        let journal_path = if args.command().uses_journal() {
+            env.journal_path.clone()
+        } else {
+            None
+        };
+        let context_config = ContextConfig {
+            dry_run: args.dry_run,
+            dry_run_output: args.dry_run_output.clone(),
+            journal_path,
+            ..ContextConfig::from(env)
+        };
+        let context = match Context::try_new(context_config).await {
             Ok(c) => c,
             Err(error) => {
                 error!(?error, "Failed to create context");
@@ -50,13 +84,13 @@ impl Indexer<ReqwestTransport> {
             }
         };
 
-        let checkpoint_slots = args.slots_per_save;
-        let disabled_checkpoint = if args.disable_sync_checkpoint_save {
+        let checkpoint_slots = env.slots_per_save;
+        let disabled_checkpoint = if env.disable_sync_checkpoint_save {
             Some(CheckpointType::Disabled)
         } else {
             None
         };
-        let num_threads = match args.num_threads {
+        let num_threads = match env.num_threads {
             Some(num_threads) => num_threads,
             None => thread::available_parallelism()
                 .map_err(|err| {
@@ -67,33 +101,76 @@ impl Indexer<ReqwestTransport> {
                 })?
                 .get() as u32,
         };
-        let disable_sync_historical = args.disable_sync_historical;
-
         let dencun_fork_slot = env
             .dencun_fork_slot
             .unwrap_or(env.network_name.dencun_fork_slot());
 
-        Ok(Self {
+        HEALTH.configure(env.network_name.seconds_per_slot(), env.health_max_head_lag);
+
+        let indexer = Self {
             context: Box::new(context),
             dencun_fork_slot,
-            disable_sync_historical,
+            dry_run: args.dry_run,
             checkpoint_slots,
             disabled_checkpoint,
             num_threads,
-        })
+        };
+
+        indexer.start_rollup_registry_reload_task()?;
+
+        Ok(indexer)
     }
+}
 
+impl<T> Indexer<T>
+where
+    T: Transport + Clone + Send + Sync + 'static,
+{
     pub async fn run(
         &mut self,
         start_block_id: Option<BlockId>,
         end_block_id: Option<BlockId>,
+        mode: SyncMode,
     ) -> IndexerResult<()> {
-        let sync_state = match self.context.blobscan_client().get_sync_state().await {
-            Ok(state) => state,
-            Err(error) => {
-                error!(?error, "Failed to fetch blobscan's sync state");
+        let journal_sync_state = match self.context.journal() {
+            Some(journal) => {
+                let in_flight_chunks = journal
+                    .get_in_flight_chunks()
+                    .await
+                    .map_err(IndexerError::JournalReadFailure)?;
+
+                if !in_flight_chunks.is_empty() {
+                    info!(
+                        ?in_flight_chunks,
+                        "Resuming unfinished chunks. Their indexed slots will be skipped"
+                    );
+                }
+
+                journal
+                    .get_sync_state()
+                    .await
+                    .map_err(IndexerError::JournalReadFailure)?
+            }
+            None => None,
+        };
+        let sync_state = match (
+            self.context.sink().get_sync_state().await,
+            journal_sync_state,
+        ) {
+            (Ok(state), Some(journal_state)) => Some(merge_sync_states(state, journal_state)),
+            (Ok(state), None) => state,
+            (Err(error), Some(journal_state)) => {
+                warn!(
+                    ?error,
+                    "Failed to fetch sync state. Resuming from the local journal"
+                );
+
+                Some(journal_state)
+            }
+            (Err(error), None) => {
+                error!(?error, "Failed to fetch sync state");
 
-                return Err(IndexerError::BlobscanSyncStateRetrievalError(error));
+                return Err(IndexerError::SyncStateRetrievalError(error));
             }
         };
 
@@ -136,7 +213,7 @@ impl Indexer<ReqwestTransport> {
         let tx1 = tx.clone();
         let mut total_tasks = 0;
 
-        if end_block_id.is_none() {
+        if mode != SyncMode::Historical {
             self.start_live_indexing_task(tx, last_synced_block, start_block_id);
             total_tasks += 1;
         }
@@ -144,10 +221,16 @@ impl Indexer<ReqwestTransport> {
         let historical_sync_completed =
             matches!(current_lower_block_id, BlockId::Slot(slot) if slot < self.dencun_fork_slot);
 
-        if !self.disable_sync_historical && !historical_sync_completed {
+        if mode != SyncMode::Live && !historical_sync_completed {
             let historical_sync_final_block_id =
                 end_block_id.unwrap_or(BlockId::Slot(self.dencun_fork_slot - 1));
 
+            // Only the historical synchronizer queues failed chunks. Retries in dry runs would be
+            // reported as succeeded without removing the chunks from the queue
+            if !self.dry_run {
+                self.start_failed_chunks_retry_task();
+            }
+
             self.start_historical_indexing_task(
                 tx1,
                 current_lower_block_id,
@@ -157,6 +240,12 @@ impl Indexer<ReqwestTransport> {
             total_tasks += 1;
         }
 
+        if total_tasks == 0 {
+            info!("Historical syncing already completed. Nothing to index");
+
+            return Ok(());
+        }
+
         let mut completed_tasks = 0;
 
         while let Some(message) = rx.recv().await {
@@ -179,13 +268,36 @@ impl Indexer<ReqwestTransport> {
         Ok(())
     }
 
+    pub async fn repair(
+        &self,
+        start_block_id: BlockId,
+        end_block_id: BlockId,
+    ) -> Result<RepairSummary, RepairerError> {
+        Repairer::new(self.context.clone(), self.num_threads)
+            .repair(start_block_id, end_block_id)
+            .await
+    }
+
+    pub async fn get_failed_chunks(&self) -> Result<Vec<FailedSlotsChunk>, RetrierError> {
+        Retrier::new(self.context.clone()).get_failed_chunks().await
+    }
+
+    pub async fn retry_failed_chunks(
+        &self,
+        chunk_ids: &[u32],
+    ) -> Result<RetrySummary, RetrierError> {
+        Retrier::new(self.context.clone()).retry(chunk_ids).await
+    }
+
     fn start_historical_indexing_task(
         &self,
         tx: mpsc::Sender<IndexerTaskMessage>,
         start_block_id: BlockId,
         end_block_id: BlockId,
     ) -> JoinHandle<IndexerResult<()>> {
-        let mut synchronizer = self.create_synchronizer(CheckpointType::Lower, None);
+        let mut synchronizer = self.create_synchronizer(CheckpointType::Lower, None, true);
+
+        HEALTH.set_historical_task_status(TaskStatus::Running);
 
         tokio::spawn(async move {
             let historical_syc_thread_span = tracing::info_span!("indexer:historical");
@@ -194,11 +306,15 @@ impl Indexer<ReqwestTransport> {
                 let result = synchronizer.sync_blocks(start_block_id, end_block_id).await;
 
                 if let Err(error) = result {
+                    HEALTH.set_historical_task_status(TaskStatus::Failed);
+
                     tx.send(IndexerTaskMessage::Error(
                         HistoricalIndexingError::SynchronizerError(error).into(),
                     ))
                     .await?;
                 } else {
+                    HEALTH.set_historical_task_status(TaskStatus::Completed);
+
                     info!("Historical syncing completed successfully");
 
                     tx.send(IndexerTaskMessage::Done).await?;
@@ -223,11 +339,25 @@ impl Indexer<ReqwestTransport> {
     ) -> JoinHandle<IndexerResult<()>> {
         let task_context = self.context.clone();
 
-        let synchronizer = self.create_synchronizer(CheckpointType::Upper, last_indexed_block);
+        let synchronizer =
+            self.create_synchronizer(CheckpointType::Upper, last_indexed_block, false);
         let realtime_sync_task_span = tracing::info_span!("indexer:live");
 
+        HEALTH.set_live_task_status(TaskStatus::Starting);
+
         tokio::spawn(async move {
             let result: Result<(), LiveIndexingError> = async {
+                match task_context.beacon_client().get_genesis().await {
+                    Ok(Some(genesis)) => HEALTH.set_genesis_time(genesis.genesis_time.into()),
+                    Ok(None) => warn!("Beacon node genesis not found. Head lag won't be reported"),
+                    Err(error) => {
+                        warn!(
+                            ?error,
+                            "Failed to fetch beacon node genesis. Head lag won't be reported"
+                        )
+                    }
+                }
+
                 let topics = vec![Topic::Head, Topic::FinalizedCheckpoint];
                 let events = topics
                     .iter()
@@ -245,6 +375,8 @@ impl Indexer<ReqwestTransport> {
                         .subscribe_to_events(&topics)
                         .map_err(LiveIndexingError::BeaconEventsSubscriptionError)?;
 
+                    HEALTH.set_live_task_status(TaskStatus::Running);
+
                     info!("Subscribed to beacon SSE stream: {}", events);
 
                     while let Some(event) = event_source.next().await {
@@ -279,8 +411,23 @@ impl Indexer<ReqwestTransport> {
                                 event_source.close();
 
                                 if let reqwest_eventsource::Error::StreamEnded = error {
+                                    HEALTH.set_live_task_status(TaskStatus::Starting);
+
                                     info!("Beacon node SSE stream ended. Resubscribing to stream…");
 
+                                    metrics::SSE_RECONNECTS.inc();
+
+                                    break;
+                                } else if task_context.beacon_client().fail_over() {
+                                    HEALTH.set_live_task_status(TaskStatus::Starting);
+
+                                    warn!(
+                                        ?error,
+                                        "Beacon node SSE stream failed. Resubscribing to a different node…"
+                                    );
+
+                                    metrics::SSE_RECONNECTS.inc();
+
                                     break;
                                 } else {
                                     return Err(error.into());
@@ -294,8 +441,12 @@ impl Indexer<ReqwestTransport> {
             .await;
 
             if let Err(error) = result {
+                HEALTH.set_live_task_status(TaskStatus::Failed);
+
                 tx.send(IndexerTaskMessage::Error(error.into())).await?;
             } else {
+                HEALTH.set_live_task_status(TaskStatus::Completed);
+
                 tx.send(IndexerTaskMessage::Done).await?;
             }
 
@@ -303,10 +454,94 @@ impl Indexer<ReqwestTransport> {
         })
     }
 
+    /// Keeps retrying the failed slots chunks queued in the sink, backing off while they keep
+    /// failing.
+    fn start_failed_chunks_retry_task(&self) -> JoinHandle<()> {
+        let retrier = Retrier::new(self.context.clone());
+        let retry_task_span = tracing::info_span!("indexer:retrier");
+
+        tokio::spawn(
+            async move {
+                let mut backoff = ExponentialBackoffBuilder::default()
+                    .with_initial_interval(FAILED_CHUNKS_POLL_INTERVAL)
+                    .with_max_interval(FAILED_CHUNKS_RETRY_MAX_INTERVAL)
+                    .with_max_elapsed_time(None)
+                    .build();
+
+                loop {
+                    let delay = match retrier.retry(&[]).await {
+                        Ok(summary) if summary.failed_chunks.is_empty() => {
+                            if !summary.is_empty() {
+                                info!(
+                                    retried_chunks = summary.retried_chunks.len(),
+                                    "Failed slots chunks indexed"
+                                );
+                            }
+
+                            backoff.reset();
+
+                            FAILED_CHUNKS_POLL_INTERVAL
+                        }
+                        Ok(summary) => {
+                            let delay = backoff
+                                .next_backoff()
+                                .unwrap_or(FAILED_CHUNKS_RETRY_MAX_INTERVAL);
+
+                            warn!(
+                                retried_chunks = summary.retried_chunks.len(),
+                                failed_chunks = summary.failed_chunks.len(),
+                                "Some failed slots chunks failed again. Retrying in {} seconds…",
+                                delay.as_secs()
+                            );
+
+                            delay
+                        }
+                        Err(error) => {
+                            let delay = backoff
+                                .next_backoff()
+                                .unwrap_or(FAILED_CHUNKS_RETRY_MAX_INTERVAL);
+
+                            error!(
+                                ?error,
+                                "Failed to retry failed slots chunks. Retrying in {} seconds…",
+                                delay.as_secs()
+                            );
+
+                            delay
+                        }
+                    };
+
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            .instrument(retry_task_span),
+        )
+    }
+
+    /// Reloads the rollup registry whenever the process receives a SIGHUP.
+    #[allow(clippy::result_large_err)]
+    fn start_rollup_registry_reload_task(&self) -> IndexerResult<()> {
+        let task_context = self.context.clone();
+        let mut sighup = signal(SignalKind::hangup()).map_err(|err| {
+            IndexerError::CreationFailure(anyhow!("Failed to listen for SIGHUP signals: {:?}", err))
+        })?;
+
+        tokio::spawn(async move {
+            while sighup.recv().await.is_some() {
+                if let Err(error) = task_context.rollup_registry().reload() {
+                    error!(?error, "Failed to reload rollup registry");
+                }
+            }
+        });
+
+        Ok(())
+    }
+
     fn create_synchronizer(
         &self,
         checkpoint_type: CheckpointType,
         last_synced_block: Option<BlockHeader>,
+        track_failed_chunks: bool,
     ) -> Box<dyn CommonSynchronizer> {
         let mut synchronizer_builder = SynchronizerBuilder::new();
 
@@ -324,6 +559,15 @@ impl Indexer<ReqwestTransport> {
 
         synchronizer_builder.with_num_threads(self.num_threads);
 
+        // Each worker submits its own index batches, so it needs enough slots to fill them up
+        if let Some(index_batch_config) = self.context.index_batch_config() {
+            let max_blocks = u32::try_from(index_batch_config.max_blocks).unwrap_or(u32::MAX);
+
+            synchronizer_builder.with_slots_per_batch(DEFAULT_SLOTS_PER_BATCH.max(max_blocks));
+        }
+
+        synchronizer_builder.with_failed_chunks_tracking(track_failed_chunks);
+
         Box::new(synchronizer_builder.build(self.context.clone()))
     }
 }
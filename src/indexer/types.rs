@@ -6,3 +6,14 @@ pub enum IndexerTaskMessage {
     Done,
     Error(IndexingError),
 }
+
+/// Indexing tasks run by the indexer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyncMode {
+    /// Sync historical blocks and follow the chain head.
+    Full,
+    /// Only follow the chain head.
+    Live,
+    /// Only sync historical blocks.
+    Historical,
+}
@@ -0,0 +1,17 @@
+use crate::{
+    clients::beacon::types::BlockIdResolutionError, slots_processor::error::SlotProcessingError,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum InspectorError {
+    #[error(transparent)]
+    FailedBlockIdResolution(#[from] BlockIdResolutionError),
+    #[error("Failed to process slot {slot}: {error}")]
+    FailedSlotProcessing {
+        slot: u32,
+        #[source]
+        error: SlotProcessingError,
+    },
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
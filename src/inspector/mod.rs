@@ -0,0 +1,175 @@
+use std::fmt;
+
+use alloy::transports::Transport;
+use serde_json::Value;
+
+use crate::{
+    clients::{
+        beacon::types::{BlockId, BlockIdResolution},
+        blobscan::types::{BlockchainSyncState, IndexRequest},
+        common::ClientResult,
+    },
+    context::CommonContext,
+    slots_processor::SlotsProcessor,
+};
+
+use self::error::InspectorError;
+
+pub mod error;
+
+/// Reads the indexer state and dry-runs block processing without indexing anything.
+pub struct Inspector<T> {
+    context: Box<dyn CommonContext<T>>,
+}
+
+impl<T> Inspector<T>
+where
+    T: Transport + Clone + Send + Sync + 'static,
+{
+    pub fn new(context: Box<dyn CommonContext<T>>) -> Self {
+        Self { context }
+    }
+
+    pub async fn get_sync_state(&self) -> ClientResult<Option<BlockchainSyncState>> {
+        self.context.sink().get_sync_state().await
+    }
+
+    /// Returns the index request that would be sent for the given block, or `None` if it has no
+    /// blobs.
+    pub async fn get_index_request(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<IndexRequest>, InspectorError> {
+        let slot = block_id
+            .resolve_to_slot(self.context.beacon_client())
+            .await?;
+
+        SlotsProcessor::new(self.context.clone(), None)
+            .build_index_request(slot)
+            .await
+            .map_err(|error| InspectorError::FailedSlotProcessing { slot, error })
+    }
+}
+
+/// Serializes the index request as JSON, replacing the blob data with its size.
+pub fn format_index_request_json(index_request: &IndexRequest) -> Result<String, InspectorError> {
+    let mut value = serde_json::to_value(index_request).map_err(anyhow::Error::from)?;
+
+    if let Some(blobs) = value.get_mut("blobs").and_then(Value::as_array_mut) {
+        for (blob, blob_entity) in blobs.iter_mut().zip(index_request.blobs.iter()) {
+            if let Some(data) = &blob_entity.data {
+                blob["data"] = Value::String(format!("[{} bytes elided]", data.len()));
+            }
+        }
+    }
+
+    Ok(serde_json::to_string_pretty(&value).map_err(anyhow::Error::from)?)
+}
+
+/// Displays an index request as a set of human-readable tables.
+pub struct IndexRequestTable<'a>(pub &'a IndexRequest);
+
+impl fmt::Display for IndexRequestTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let IndexRequest {
+            block,
+            transactions,
+            blobs,
+        } = self.0;
+
+        writeln!(f, "Block")?;
+        writeln!(f, "  Slot:            {}", block.slot)?;
+        writeln!(f, "  Number:          {}", block.number)?;
+        writeln!(f, "  Hash:            {}", block.hash)?;
+        writeln!(f, "  Timestamp:       {}", block.timestamp)?;
+        writeln!(f, "  Blob gas used:   {}", block.blob_gas_used)?;
+        writeln!(f, "  Excess blob gas: {}", block.excess_blob_gas)?;
+        writeln!(
+            f,
+            "  Blob base fee:   {}",
+            block
+                .blob_base_fee
+                .map(|blob_base_fee| blob_base_fee.to_string())
+                .unwrap_or_default()
+        )?;
+        writeln!(
+            f,
+            "  Fork:            {}",
+            block.fork.map(|fork| fork.as_str()).unwrap_or_default()
+        )?;
+
+        writeln!(f, "\nTransactions ({})", transactions.len())?;
+        writeln!(
+            f,
+            "  {:<5} {:<66} {:<42} {:<42} {:>20} {:>20} {:>20} {:>14} {:<7} {:<12}",
+            "INDEX",
+            "HASH",
+            "FROM",
+            "TO",
+            "GAS PRICE",
+            "MAX FEE PER BLOB GAS",
+            "BLOB GAS PRICE",
+            "BLOB GAS USED",
+            "STATUS",
+            "ROLLUP"
+        )?;
+
+        for tx in transactions.iter() {
+            writeln!(
+                f,
+                "  {:<5} {:<66} {:<42} {:<42} {:>20} {:>20} {:>20} {:>14} {:<7} {:<12}",
+                tx.index,
+                tx.hash,
+                tx.from,
+                tx.to.map(|to| to.to_string()).unwrap_or_default(),
+                tx.gas_price,
+                tx.max_fee_per_blob_gas,
+                tx.blob_gas_price
+                    .map(|value| value.to_string())
+                    .unwrap_or_default(),
+                tx.blob_gas_used
+                    .map(|value| value.to_string())
+                    .unwrap_or_default(),
+                match tx.status {
+                    Some(true) => "success",
+                    Some(false) => "failed",
+                    None => "",
+                },
+                tx.rollup.as_deref().unwrap_or_default()
+            )?;
+        }
+
+        writeln!(f, "\nBlobs ({})", blobs.len())?;
+        writeln!(
+            f,
+            "  {:<66} {:<66} {:<5} {:>8} {:>8} {:<8} COMMITMENT",
+            "VERSIONED HASH", "TX HASH", "INDEX", "SIZE", "USED", "ENCODING"
+        )?;
+
+        for blob in blobs.iter() {
+            writeln!(
+                f,
+                "  {:<66} {:<66} {:<5} {:>8} {:>8} {:<8} {}",
+                blob.versioned_hash,
+                blob.tx_hash,
+                blob.index,
+                blob.data
+                    .as_ref()
+                    .map(|data| data.len())
+                    .unwrap_or_default(),
+                blob.metadata
+                    .as_ref()
+                    .map(|metadata| metadata.used_bytes.to_string())
+                    .unwrap_or_default(),
+                blob.metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.encoding)
+                    .map(|encoding| encoding.as_str())
+                    .unwrap_or_default(),
+                blob.commitment
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,394 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Context;
+use redb::{
+    Database, Durability, ReadTransaction, ReadableTable, TableDefinition, WriteTransaction,
+};
+
+use crate::clients::{beacon::types::BlockHeader, blobscan::types::BlockchainSyncState};
+
+/// Block root of each indexed slot not covered by a completed chunk yet.
+const INDEXED_SLOTS: TableDefinition<u32, &[u8]> = TableDefinition::new("indexed_slots");
+/// Slot ranges being processed, given as initial and final slot.
+const IN_FLIGHT_CHUNKS: TableDefinition<(u32, u32), ()> = TableDefinition::new("in_flight_chunks");
+const SYNC_STATE: TableDefinition<&str, &str> = TableDefinition::new("sync_state");
+const SYNC_STATE_KEY: &str = "sync_state";
+
+/// Local on-disk journal recording the indexing progress, so the indexer can resume from
+/// where it stopped without depending on the sink.
+///
+/// Indexed slots are recorded one by one while a chunk is in flight and pruned once the chunk is
+/// completed and its checkpoint saved.
+pub struct Journal {
+    db: Arc<Database>,
+}
+
+impl Journal {
+    pub fn try_new(path: &Path) -> Result<Self, anyhow::Error> {
+        let db = Database::create(path)
+            .with_context(|| format!("Failed to open journal {}", path.display()))?;
+
+        // Create the tables up front so they can be read before anything is written
+        let tx = db.begin_write()?;
+
+        tx.open_table(INDEXED_SLOTS)?;
+        tx.open_table(IN_FLIGHT_CHUNKS)?;
+        tx.open_table(SYNC_STATE)?;
+        tx.commit()?;
+
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    /// Returns whether the given block was already indexed by an unfinished chunk.
+    pub async fn is_block_indexed(
+        &self,
+        block_header: &BlockHeader,
+    ) -> Result<bool, anyhow::Error> {
+        let slot = block_header.slot;
+        let root = block_header.root;
+
+        self.read(move |tx| {
+            let indexed_root = tx.open_table(INDEXED_SLOTS)?.get(slot)?;
+
+            Ok(indexed_root.is_some_and(|indexed_root| indexed_root.value() == root.as_slice()))
+        })
+        .await
+    }
+
+    /// Records an indexed block without waiting for it to reach the disk. A block lost on a crash
+    /// is just indexed again, and the next chunk completion persists all previous records.
+    pub async fn record_indexed_block(
+        &self,
+        block_header: &BlockHeader,
+    ) -> Result<(), anyhow::Error> {
+        let slot = block_header.slot;
+        let root = block_header.root;
+
+        self.write(Durability::Eventual, move |tx| {
+            tx.open_table(INDEXED_SLOTS)?
+                .insert(slot, root.as_slice())?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn start_chunk(
+        &self,
+        initial_slot: u32,
+        final_slot: u32,
+    ) -> Result<(), anyhow::Error> {
+        self.write(Durability::Immediate, move |tx| {
+            tx.open_table(IN_FLIGHT_CHUNKS)?
+                .insert((initial_slot, final_slot), ())?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Removes a chunk that failed to be processed. The records of its indexed slots are kept, so
+    /// they are skipped when the slots are synced again, until a chunk covering them completes.
+    pub async fn abandon_chunk(
+        &self,
+        initial_slot: u32,
+        final_slot: u32,
+    ) -> Result<(), anyhow::Error> {
+        self.write(Durability::Immediate, move |tx| {
+            tx.open_table(IN_FLIGHT_CHUNKS)?
+                .remove((initial_slot, final_slot))?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Removes a processed chunk along with the records of its indexed slots, saving the given
+    /// sync state fields, if any.
+    pub async fn complete_chunk(
+        &self,
+        initial_slot: u32,
+        final_slot: u32,
+        sync_state: Option<BlockchainSyncState>,
+    ) -> Result<(), anyhow::Error> {
+        self.write(Durability::Immediate, move |tx| {
+            let chunk_slots = initial_slot.min(final_slot)..initial_slot.max(final_slot);
+
+            tx.open_table(IN_FLIGHT_CHUNKS)?
+                .remove((initial_slot, final_slot))?;
+            tx.open_table(INDEXED_SLOTS)?
+                .retain_in(chunk_slots, |_, _| false)?;
+
+            if let Some(sync_state) = sync_state {
+                let mut table = tx.open_table(SYNC_STATE)?;
+                let mut new_sync_state = match table.get(SYNC_STATE_KEY)? {
+                    Some(value) => serde_json::from_str::<BlockchainSyncState>(value.value())?,
+                    None => BlockchainSyncState::default(),
+                };
+
+                update_sync_state(&mut new_sync_state, &sync_state);
+
+                table.insert(
+                    SYNC_STATE_KEY,
+                    serde_json::to_string(&new_sync_state)?.as_str(),
+                )?;
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn get_sync_state(&self) -> Result<Option<BlockchainSyncState>, anyhow::Error> {
+        self.read(|tx| match tx.open_table(SYNC_STATE)?.get(SYNC_STATE_KEY)? {
+            Some(value) => Ok(Some(serde_json::from_str(value.value())?)),
+            None => Ok(None),
+        })
+        .await
+    }
+
+    pub async fn get_in_flight_chunks(&self) -> Result<Vec<(u32, u32)>, anyhow::Error> {
+        self.read(|tx| {
+            let table = tx.open_table(IN_FLIGHT_CHUNKS)?;
+
+            table.iter()?.map(|entry| Ok(entry?.0.value())).collect()
+        })
+        .await
+    }
+
+    /// Runs a read transaction on a blocking thread, keeping disk reads off the async runtime.
+    async fn read<F, R>(&self, read: F) -> Result<R, anyhow::Error>
+    where
+        F: FnOnce(&ReadTransaction) -> Result<R, anyhow::Error> + Send + 'static,
+        R: Send + 'static,
+    {
+        let db = self.db.clone();
+
+        tokio::task::spawn_blocking(move || read(&db.begin_read()?)).await?
+    }
+
+    /// Runs a write transaction on a blocking thread, as commits wait for the disk and for any
+    /// other writer holding the database.
+    async fn write<F>(&self, durability: Durability, write: F) -> Result<(), anyhow::Error>
+    where
+        F: FnOnce(&WriteTransaction) -> Result<(), anyhow::Error> + Send + 'static,
+    {
+        let db = self.db.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let mut tx = db.begin_write()?;
+
+            tx.set_durability(durability);
+            write(&tx)?;
+            tx.commit()?;
+
+            Ok(())
+        })
+        .await?
+    }
+}
+
+/// Overwrites the fields of a sync state with the ones set in the given update.
+fn update_sync_state(sync_state: &mut BlockchainSyncState, update: &BlockchainSyncState) {
+    if update.last_finalized_block.is_some() {
+        sync_state.last_finalized_block = update.last_finalized_block;
+    }
+
+    if update.last_lower_synced_slot.is_some() {
+        sync_state.last_lower_synced_slot = update.last_lower_synced_slot;
+    }
+
+    if update.last_upper_synced_slot.is_some() {
+        sync_state.last_upper_synced_slot = update.last_upper_synced_slot;
+        sync_state.last_upper_synced_block_root = update.last_upper_synced_block_root;
+        sync_state.last_upper_synced_block_slot = update.last_upper_synced_block_slot;
+    }
+}
+
+/// Combines the sync state stored in the sink with the journal one, keeping the furthest synced
+/// slots of each. The journal may be ahead when the last checkpoints failed to be saved.
+pub fn merge_sync_states(
+    sink_sync_state: Option<BlockchainSyncState>,
+    journal_sync_state: BlockchainSyncState,
+) -> BlockchainSyncState {
+    let mut sync_state = match sink_sync_state {
+        Some(sync_state) => sync_state,
+        None => return journal_sync_state,
+    };
+
+    if let Some(journal_lower_slot) = journal_sync_state.last_lower_synced_slot {
+        if sync_state
+            .last_lower_synced_slot
+            .is_none_or(|slot| journal_lower_slot < slot)
+        {
+            sync_state.last_lower_synced_slot = Some(journal_lower_slot);
+        }
+    }
+
+    if let Some(journal_upper_slot) = journal_sync_state.last_upper_synced_slot {
+        if sync_state
+            .last_upper_synced_slot
+            .is_none_or(|slot| journal_upper_slot > slot)
+        {
+            sync_state.last_upper_synced_slot = Some(journal_upper_slot);
+            sync_state.last_upper_synced_block_root =
+                journal_sync_state.last_upper_synced_block_root;
+            sync_state.last_upper_synced_block_slot =
+                journal_sync_state.last_upper_synced_block_slot;
+        }
+    }
+
+    sync_state
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use alloy::primitives::B256;
+
+    use super::*;
+
+    /// Returns a fresh journal path in the temporary directory, named after the test using it.
+    fn journal_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("blob-indexer-journal-{name}.redb"));
+
+        let _ = std::fs::remove_file(&path);
+
+        path
+    }
+
+    fn block_header(slot: u32) -> BlockHeader {
+        BlockHeader {
+            root: B256::repeat_byte(slot as u8),
+            parent_root: B256::ZERO,
+            slot,
+        }
+    }
+
+    fn sync_state(
+        last_lower_synced_slot: Option<u32>,
+        last_upper_synced_slot: Option<u32>,
+    ) -> BlockchainSyncState {
+        BlockchainSyncState {
+            last_finalized_block: None,
+            last_lower_synced_slot,
+            last_upper_synced_slot,
+            last_upper_synced_block_root: last_upper_synced_slot
+                .map(|slot| B256::repeat_byte(slot as u8)),
+            last_upper_synced_block_slot: last_upper_synced_slot,
+        }
+    }
+
+    #[tokio::test]
+    async fn resumes_in_flight_chunk_after_reopening() {
+        let path = journal_path("resume");
+        let journal = Journal::try_new(&path).unwrap();
+
+        journal.start_chunk(100, 120).await.unwrap();
+        journal
+            .record_indexed_block(&block_header(105))
+            .await
+            .unwrap();
+
+        drop(journal);
+
+        let journal = Journal::try_new(&path).unwrap();
+
+        assert_eq!(
+            journal.get_in_flight_chunks().await.unwrap(),
+            vec![(100, 120)]
+        );
+        assert!(journal.is_block_indexed(&block_header(105)).await.unwrap());
+        assert!(!journal.is_block_indexed(&block_header(106)).await.unwrap());
+
+        // A block reorged out of the slot isn't considered indexed
+        let reorged_block = BlockHeader {
+            root: B256::repeat_byte(0xff),
+            ..block_header(105)
+        };
+
+        assert!(!journal.is_block_indexed(&reorged_block).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn completes_chunk_saving_its_sync_state() {
+        let path = journal_path("complete");
+        let journal = Journal::try_new(&path).unwrap();
+
+        journal.start_chunk(100, 120).await.unwrap();
+        journal
+            .record_indexed_block(&block_header(105))
+            .await
+            .unwrap();
+        journal
+            .complete_chunk(100, 120, Some(sync_state(None, Some(119))))
+            .await
+            .unwrap();
+        // Later updates only overwrite the fields they set
+        journal
+            .complete_chunk(50, 40, Some(sync_state(Some(41), None)))
+            .await
+            .unwrap();
+
+        drop(journal);
+
+        let journal = Journal::try_new(&path).unwrap();
+
+        assert!(journal.get_in_flight_chunks().await.unwrap().is_empty());
+        assert!(!journal.is_block_indexed(&block_header(105)).await.unwrap());
+        assert_eq!(
+            journal.get_sync_state().await.unwrap(),
+            Some(sync_state(Some(41), Some(119)))
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_indexed_slots_of_abandoned_chunks() {
+        let path = journal_path("abandon");
+        let journal = Journal::try_new(&path).unwrap();
+
+        journal.start_chunk(100, 120).await.unwrap();
+        journal
+            .record_indexed_block(&block_header(105))
+            .await
+            .unwrap();
+        journal.abandon_chunk(100, 120).await.unwrap();
+
+        assert!(journal.get_in_flight_chunks().await.unwrap().is_empty());
+        assert!(journal.is_block_indexed(&block_header(105)).await.unwrap());
+        assert!(journal.get_sync_state().await.unwrap().is_none());
+    }
+
+    #[test]
+    fn merges_furthest_synced_slots_of_sink_and_journal() {
+        let sink_sync_state = sync_state(Some(50), Some(200));
+        let journal_sync_state = sync_state(Some(40), Some(180));
+
+        assert_eq!(
+            merge_sync_states(Some(sink_sync_state), journal_sync_state),
+            sync_state(Some(40), Some(200))
+        );
+    }
+
+    #[test]
+    fn merges_journal_sync_state_ahead_of_sink() {
+        let sink_sync_state = sync_state(None, Some(200));
+        let journal_sync_state = sync_state(Some(40), Some(220));
+
+        assert_eq!(
+            merge_sync_states(Some(sink_sync_state), journal_sync_state),
+            sync_state(Some(40), Some(220))
+        );
+    }
+
+    #[test]
+    fn merges_journal_sync_state_without_sink_one() {
+        let journal_sync_state = sync_state(Some(40), Some(220));
+
+        assert_eq!(
+            merge_sync_states(None, journal_sync_state.clone()),
+            journal_sync_state
+        );
+    }
+}
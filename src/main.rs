@@ -1,29 +1,54 @@
 use anyhow::{anyhow, Result as AnyhowResult};
-use args::Args;
+use args::{Args, Command, ConfigCommand, FailedChunksCommand, InspectCommand, OutputFormat};
 use clap::Parser;
+use context::{Config as ContextConfig, Context};
 use env::Environment;
-use indexer::Indexer;
+use indexer::{types::SyncMode, Indexer};
+use inspector::{format_index_request_json, IndexRequestTable, Inspector};
 use utils::{
     banner::print_banner,
     telemetry::{get_subscriber, init_subscriber},
 };
 
 mod args;
+mod blob_analysis;
+mod blob_storage;
 mod clients;
 mod context;
 mod env;
+mod health;
 mod indexer;
+mod inspector;
+mod journal;
+mod metrics;
 mod network;
+mod repairer;
+mod retrier;
+mod rollups;
+mod server;
+mod sinks;
 mod slots_processor;
 mod synchronizer;
 mod utils;
 
 async fn run() -> AnyhowResult<()> {
+    let args = Args::parse();
+
     dotenv::dotenv().ok();
-    let env = match Environment::from_env() {
-        Ok(env) => env,
-        Err(err) => return Err(anyhow!(format!("Failed to load env variables: {}", err))),
-    };
+    let mut env = Environment::from_config_file_and_env(args.config.as_deref())?;
+
+    env.apply_args(&args);
+
+    let command = args.command();
+
+    if let Command::Config {
+        command: ConfigCommand::Print,
+    } = command
+    {
+        print!("{}", toml::to_string(&env.redacted())?);
+
+        return Ok(());
+    }
 
     let mut _guard;
 
@@ -37,17 +62,106 @@ async fn run() -> AnyhowResult<()> {
         ));
     }
 
-    let subscriber = get_subscriber("info".into(), std::io::stdout);
-    init_subscriber(subscriber);
+    if let Command::Inspect { command } = command {
+        // Logs are written to stderr so the inspection output can be piped
+        init_subscriber(get_subscriber("info".into(), std::io::stderr));
 
-    let args = Args::parse();
+        return inspect(&env, command).await;
+    }
+
+    init_subscriber(get_subscriber("info".into(), std::io::stdout));
 
     print_banner(&args, &env);
 
-    Indexer::try_new(&env, &args)?
-        .run(args.from_slot, args.to_slot)
-        .await
-        .map_err(|err| anyhow!(err))
+    if let Some(server_address) = env.server_address {
+        server::spawn(server_address)
+            .await
+            .map_err(|err| anyhow!("Failed to bind HTTP server to {server_address}: {err}"))?;
+    }
+
+    let mut indexer = Indexer::try_new(&env, &args).await?;
+
+    let result = match command {
+        Command::Sync { from_slot } => indexer.run(from_slot, None, SyncMode::Full).await,
+        Command::Live { from_slot } => indexer.run(from_slot, None, SyncMode::Live).await,
+        Command::Backfill { from_slot, to_slot } => {
+            indexer.run(from_slot, to_slot, SyncMode::Historical).await
+        }
+        Command::Repair { from_slot, to_slot } => {
+            let summary = indexer
+                .repair(from_slot, to_slot)
+                .await
+                .map_err(|err| anyhow!(err))?;
+
+            println!("{summary}");
+
+            Ok(())
+        }
+        Command::FailedChunks { command } => match command {
+            FailedChunksCommand::List => {
+                let chunks = indexer
+                    .get_failed_chunks()
+                    .await
+                    .map_err(|err| anyhow!(err))?;
+
+                if chunks.is_empty() {
+                    println!("No failed slots chunks queued");
+                }
+
+                for chunk in chunks {
+                    println!("{chunk}");
+                }
+
+                Ok(())
+            }
+            FailedChunksCommand::Retry { ids } => {
+                let summary = indexer
+                    .retry_failed_chunks(&ids)
+                    .await
+                    .map_err(|err| anyhow!(err))?;
+
+                println!("{summary}");
+
+                Ok(())
+            }
+        },
+        Command::Inspect { .. } | Command::Config { .. } => unreachable!(),
+    };
+
+    result.map_err(|err| anyhow!(err))
+}
+
+async fn inspect(env: &Environment, command: InspectCommand) -> AnyhowResult<()> {
+    // Inspecting never writes, so the sink is opened as in a dry run
+    let context = Context::try_new(ContextConfig {
+        dry_run: true,
+        ..ContextConfig::from(env)
+    })
+    .await?;
+    let inspector = Inspector::new(Box::new(context));
+
+    match command {
+        InspectCommand::SyncState => {
+            let sync_state = inspector.get_sync_state().await?;
+
+            println!("{}", serde_json::to_string_pretty(&sync_state)?);
+        }
+        InspectCommand::Slot { block_id, format } => {
+            match inspector.get_index_request(block_id.clone()).await? {
+                Some(index_request) => match format {
+                    OutputFormat::Json => {
+                        println!("{}", format_index_request_json(&index_request)?)
+                    }
+                    OutputFormat::Table => {
+                        print!("{}", IndexRequestTable(&index_request))
+                    }
+                },
+                None => println!("Block {block_id} has no blobs to index"),
+            }
+        }
+    }
+
+    Ok(())
 }
 
 #[tokio::main]
@@ -0,0 +1,147 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge_vec, Encoder, Histogram, HistogramVec, IntCounter, IntCounterVec,
+    IntGaugeVec, TextEncoder,
+};
+use url::Url;
+
+pub static LAST_SYNCED_SLOT: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "indexer_last_synced_slot",
+        "Last slot saved as synced, by checkpoint type",
+        &["checkpoint"]
+    )
+    .unwrap()
+});
+
+pub static INDEXED_BLOCKS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "indexer_indexed_blocks_total",
+        "Total number of blocks indexed"
+    )
+    .unwrap()
+});
+
+pub static INDEXED_TRANSACTIONS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "indexer_indexed_transactions_total",
+        "Total number of blob transactions indexed"
+    )
+    .unwrap()
+});
+
+pub static INDEXED_BLOBS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "indexer_indexed_blobs_total",
+        "Total number of blobs indexed"
+    )
+    .unwrap()
+});
+
+pub static REORGS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!("indexer_reorgs_total", "Total number of reorgs handled").unwrap()
+});
+
+pub static REORG_DEPTH: LazyLock<Histogram> = LazyLock::new(|| {
+    register_histogram!(
+        "indexer_reorg_depth",
+        "Amount of blocks rewinded when handling a reorg",
+        vec![1.0, 2.0, 3.0, 5.0, 10.0, 25.0, 50.0, 100.0]
+    )
+    .unwrap()
+});
+
+pub static FAILED_SLOTS_CHUNKS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "indexer_failed_slots_chunks_total",
+        "Total number of slot ranges queued to be retried after failing to be indexed"
+    )
+    .unwrap()
+});
+
+pub static RETRIED_SLOTS_CHUNKS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "indexer_retried_slots_chunks_total",
+        "Total number of retries of failed slot ranges, by result",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub static HTTP_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "indexer_http_request_duration_seconds",
+        "Duration of the requests sent to the beacon node and Blobscan API, retries included",
+        &["method", "endpoint"]
+    )
+    .unwrap()
+});
+
+pub static HTTP_REQUEST_RETRIES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "indexer_http_request_retries_total",
+        "Total number of retried requests sent to the beacon node and Blobscan API",
+        &["method", "endpoint"]
+    )
+    .unwrap()
+});
+
+pub static SSE_RECONNECTS: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "indexer_beacon_sse_reconnects_total",
+        "Total number of times the beacon node SSE stream was resubscribed"
+    )
+    .unwrap()
+});
+
+pub static BEACON_NODES_MISMATCHES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "indexer_beacon_nodes_mismatches_total",
+        "Total number of times the secondary beacon node disagreed with the primary one, by check",
+        &["check"]
+    )
+    .unwrap()
+});
+
+/// Registers all metrics so they are exported before being updated for the first time.
+pub fn register() {
+    LazyLock::force(&LAST_SYNCED_SLOT);
+    LazyLock::force(&INDEXED_BLOCKS);
+    LazyLock::force(&INDEXED_TRANSACTIONS);
+    LazyLock::force(&INDEXED_BLOBS);
+    LazyLock::force(&REORGS);
+    LazyLock::force(&REORG_DEPTH);
+    LazyLock::force(&FAILED_SLOTS_CHUNKS);
+    LazyLock::force(&RETRIED_SLOTS_CHUNKS);
+    LazyLock::force(&HTTP_REQUEST_DURATION);
+    LazyLock::force(&HTTP_REQUEST_RETRIES);
+    LazyLock::force(&SSE_RECONNECTS);
+    LazyLock::force(&BEACON_NODES_MISMATCHES);
+}
+
+/// Returns the path of the given URL with block ids, slots and hashes replaced by a placeholder
+/// so it can be used as a low cardinality metric label.
+pub fn endpoint_label(url: &Url) -> String {
+    url.path()
+        .split('/')
+        .map(|segment| {
+            if segment.starts_with("0x") || segment.parse::<u64>().is_ok() {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Encodes all registered metrics using the Prometheus text format.
+pub fn encode() -> Result<String, anyhow::Error> {
+    let mut buffer = vec![];
+
+    TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+
+    Ok(String::from_utf8(buffer)?)
+}
@@ -25,3 +25,423 @@ impl Network {
         }
     }
 }
+
+impl Network {
+    pub fn seconds_per_slot(&self) -> u64 {
+        match self {
+            Network::Gnosis | Network::Chiado => 5,
+            _ => 12,
+        }
+    }
+
+    pub fn slots_per_epoch(&self) -> u32 {
+        match self {
+            Network::Gnosis | Network::Chiado => 16,
+            _ => 32,
+        }
+    }
+
+    /// Returns the slot at which the given fork activates, if it's scheduled.
+    pub fn fork_slot(&self, fork: Fork) -> Option<u32> {
+        let epoch = match fork {
+            Fork::Deneb => return Some(self.dencun_fork_slot()),
+            Fork::Electra => match self {
+                Network::Mainnet => Some(364032),
+                Network::Sepolia => Some(222464),
+                Network::Holesky => Some(115968),
+                Network::Gnosis => Some(1337856),
+                Network::Chiado => Some(948224),
+                Network::Goerli | Network::Devnet => None,
+            },
+            Fork::Fulu => match self {
+                Network::Mainnet => Some(411392),
+                Network::Sepolia => Some(272640),
+                Network::Holesky => Some(165120),
+                _ => None,
+            },
+        };
+
+        epoch.map(|epoch| epoch * self.slots_per_epoch())
+    }
+}
+
+/// Consensus layer forks that changed how blobs are handled.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Fork {
+    Deneb,
+    Electra,
+    Fulu,
+}
+
+impl Fork {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Fork::Deneb => "deneb",
+            Fork::Electra => "electra",
+            Fork::Fulu => "fulu",
+        }
+    }
+}
+
+/// Activation slots of the forks of a network.
+#[derive(Debug, Clone)]
+pub struct ForkSchedule {
+    deneb_slot: u32,
+    electra_slot: Option<u32>,
+    fulu_slot: Option<u32>,
+}
+
+impl ForkSchedule {
+    /// Creates the schedule of the given network, with the activation slots optionally
+    /// overridden for custom networks.
+    pub fn new(
+        network: &Network,
+        deneb_slot: Option<u32>,
+        electra_slot: Option<u32>,
+        fulu_slot: Option<u32>,
+    ) -> Self {
+        Self {
+            deneb_slot: deneb_slot.unwrap_or(network.dencun_fork_slot()),
+            electra_slot: electra_slot.or(network.fork_slot(Fork::Electra)),
+            fulu_slot: fulu_slot.or(network.fork_slot(Fork::Fulu)),
+        }
+    }
+
+    /// Returns the fork the given slot belongs to. Slots before Deneb, which have no blobs, are
+    /// considered part of it.
+    pub fn fork_at(&self, slot: u32) -> Fork {
+        [Fork::Fulu, Fork::Electra]
+            .into_iter()
+            .find(|fork| {
+                self.fork_slot(*fork)
+                    .is_some_and(|fork_slot| slot >= fork_slot)
+            })
+            .unwrap_or(Fork::Deneb)
+    }
+
+    pub fn fork_slot(&self, fork: Fork) -> Option<u32> {
+        match fork {
+            Fork::Deneb => Some(self.deneb_slot),
+            Fork::Electra => self.electra_slot,
+            Fork::Fulu => self.fulu_slot,
+        }
+    }
+}
+
+/// Blob parameters in effect from a given fork or blob parameters only fork.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobParameters {
+    pub target_blobs_per_block: u32,
+    pub max_blobs_per_block: u32,
+    pub base_fee_update_fraction: u64,
+    pub min_base_fee_per_blob_gas: u64,
+}
+
+const DENEB_BLOB_PARAMETERS: BlobParameters = BlobParameters {
+    target_blobs_per_block: 3,
+    max_blobs_per_block: 6,
+    base_fee_update_fraction: 3338477,
+    min_base_fee_per_blob_gas: 1,
+};
+
+const ELECTRA_BLOB_PARAMETERS: BlobParameters = BlobParameters {
+    target_blobs_per_block: 6,
+    max_blobs_per_block: 9,
+    base_fee_update_fraction: 5007716,
+    min_base_fee_per_blob_gas: 1,
+};
+
+const BPO1_BLOB_PARAMETERS: BlobParameters = BlobParameters {
+    target_blobs_per_block: 10,
+    max_blobs_per_block: 15,
+    base_fee_update_fraction: 8346193,
+    min_base_fee_per_blob_gas: 1,
+};
+
+const BPO2_BLOB_PARAMETERS: BlobParameters = BlobParameters {
+    target_blobs_per_block: 14,
+    max_blobs_per_block: 21,
+    base_fee_update_fraction: 11684671,
+    min_base_fee_per_blob_gas: 1,
+};
+
+const GNOSIS_BLOB_PARAMETERS: BlobParameters = BlobParameters {
+    target_blobs_per_block: 1,
+    max_blobs_per_block: 2,
+    base_fee_update_fraction: 1112826,
+    min_base_fee_per_blob_gas: 1_000_000_000,
+};
+
+impl Network {
+    /// Returns the blob parameters a fork activates on the network.
+    fn fork_blob_parameters(&self, fork: Fork) -> BlobParameters {
+        match (self, fork) {
+            (Network::Gnosis | Network::Chiado, _) => GNOSIS_BLOB_PARAMETERS,
+            (_, Fork::Deneb) => DENEB_BLOB_PARAMETERS,
+            // Fulu keeps the Electra parameters, which are then changed by BPO forks
+            (_, Fork::Electra | Fork::Fulu) => ELECTRA_BLOB_PARAMETERS,
+        }
+    }
+
+    /// Returns the blob parameters only forks scheduled on the network, keyed by activation
+    /// epoch.
+    fn bpo_blob_parameters_by_epoch(&self) -> Vec<(u32, BlobParameters)> {
+        match self {
+            Network::Mainnet => vec![
+                (412672, BPO1_BLOB_PARAMETERS),
+                (419072, BPO2_BLOB_PARAMETERS),
+            ],
+            Network::Sepolia => vec![
+                (274176, BPO1_BLOB_PARAMETERS),
+                (275456, BPO2_BLOB_PARAMETERS),
+            ],
+            Network::Holesky => vec![
+                (166400, BPO1_BLOB_PARAMETERS),
+                (167936, BPO2_BLOB_PARAMETERS),
+            ],
+            _ => vec![],
+        }
+    }
+}
+
+/// Blob parameters only fork, changing the blob parameters from a given slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BpoFork {
+    pub slot: u32,
+    pub target_blobs_per_block: u32,
+    pub max_blobs_per_block: u32,
+    pub base_fee_update_fraction: u64,
+}
+
+/// Blob parameters only forks of a custom schedule, given as a comma-separated list of
+/// `slot:target:max:base_fee_update_fraction` entries.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct BpoSchedule(Vec<BpoFork>);
+
+impl TryFrom<String> for BpoSchedule {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let invalid_entry = || {
+                    format!(
+                        "Invalid BPO fork \"{entry}\", expected slot:target:max:base_fee_update_fraction"
+                    )
+                };
+                let values = entry.split(':').collect::<Vec<_>>();
+                let [slot, target, max, base_fee_update_fraction] = values.as_slice() else {
+                    return Err(invalid_entry());
+                };
+
+                Ok(BpoFork {
+                    slot: slot.parse().map_err(|_| invalid_entry())?,
+                    target_blobs_per_block: target.parse().map_err(|_| invalid_entry())?,
+                    max_blobs_per_block: max.parse().map_err(|_| invalid_entry())?,
+                    base_fee_update_fraction: base_fee_update_fraction
+                        .parse()
+                        .map_err(|_| invalid_entry())?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(BpoSchedule)
+    }
+}
+
+impl From<BpoSchedule> for String {
+    fn from(schedule: BpoSchedule) -> Self {
+        schedule
+            .0
+            .iter()
+            .map(|bpo_fork| {
+                format!(
+                    "{}:{}:{}:{}",
+                    bpo_fork.slot,
+                    bpo_fork.target_blobs_per_block,
+                    bpo_fork.max_blobs_per_block,
+                    bpo_fork.base_fee_update_fraction
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Blob parameters of a network over time.
+#[derive(Debug, Clone)]
+pub struct BlobSchedule {
+    /// Blob parameters keyed by activation slot, sorted by slot.
+    parameters_by_slot: Vec<(u32, BlobParameters)>,
+}
+
+impl BlobSchedule {
+    /// Creates the blob schedule of the given network from the parameters activated by each fork
+    /// of the fork schedule, followed by its blob parameters only forks. A custom BPO schedule
+    /// replaces the built-in one of the network.
+    pub fn new(
+        network: &Network,
+        fork_schedule: &ForkSchedule,
+        bpo_schedule: Option<&BpoSchedule>,
+    ) -> Self {
+        let fork_parameters = [Fork::Deneb, Fork::Electra, Fork::Fulu]
+            .into_iter()
+            .filter_map(|fork| {
+                fork_schedule
+                    .fork_slot(fork)
+                    .map(|fork_slot| (fork_slot, network.fork_blob_parameters(fork)))
+            });
+        let bpo_parameters = match bpo_schedule {
+            Some(bpo_schedule) => {
+                let min_base_fee_per_blob_gas = network
+                    .fork_blob_parameters(Fork::Deneb)
+                    .min_base_fee_per_blob_gas;
+
+                bpo_schedule
+                    .0
+                    .iter()
+                    .map(|bpo_fork| {
+                        (
+                            bpo_fork.slot,
+                            BlobParameters {
+                                target_blobs_per_block: bpo_fork.target_blobs_per_block,
+                                max_blobs_per_block: bpo_fork.max_blobs_per_block,
+                                base_fee_update_fraction: bpo_fork.base_fee_update_fraction,
+                                min_base_fee_per_blob_gas,
+                            },
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            }
+            None => network
+                .bpo_blob_parameters_by_epoch()
+                .into_iter()
+                .map(|(epoch, parameters)| (epoch * network.slots_per_epoch(), parameters))
+                .collect(),
+        };
+        let mut parameters_by_slot = fork_parameters.chain(bpo_parameters).collect::<Vec<_>>();
+
+        // The sort is stable, so BPO forks take precedence over forks activated at the same slot
+        parameters_by_slot.sort_by_key(|(slot, _)| *slot);
+
+        Self { parameters_by_slot }
+    }
+    /// Returns the blob parameters in effect at the given slot. Slots before the first change
+    /// use its parameters.
+    pub fn parameters_at(&self, slot: u32) -> &BlobParameters {
+        self.parameters_by_slot
+            .iter()
+            .rev()
+            .find(|(activation_slot, _)| slot >= *activation_slot)
+            .or(self.parameters_by_slot.first())
+            .map(|(_, parameters)| parameters)
+            .unwrap_or(&DENEB_BLOB_PARAMETERS)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &(u32, BlobParameters)> {
+        self.parameters_by_slot.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bpo_schedule(value: &str) -> BpoSchedule {
+        BpoSchedule::try_from(value.to_string()).unwrap()
+    }
+
+    #[test]
+    fn uses_built_in_mainnet_blob_schedule() {
+        let network = Network::Mainnet;
+        let blob_schedule = BlobSchedule::new(
+            &network,
+            &ForkSchedule::new(&network, None, None, None),
+            None,
+        );
+
+        assert_eq!(blob_schedule.parameters_at(8626176), &DENEB_BLOB_PARAMETERS);
+        assert_eq!(
+            blob_schedule.parameters_at(364032 * 32 - 1),
+            &DENEB_BLOB_PARAMETERS
+        );
+        assert_eq!(
+            blob_schedule.parameters_at(364032 * 32),
+            &ELECTRA_BLOB_PARAMETERS
+        );
+        assert_eq!(
+            blob_schedule.parameters_at(411392 * 32),
+            &ELECTRA_BLOB_PARAMETERS
+        );
+        assert_eq!(
+            blob_schedule.parameters_at(412672 * 32),
+            &BPO1_BLOB_PARAMETERS
+        );
+        assert_eq!(
+            blob_schedule.parameters_at(419072 * 32),
+            &BPO2_BLOB_PARAMETERS
+        );
+    }
+
+    #[test]
+    fn follows_overridden_fork_slots() {
+        let network = Network::Mainnet;
+        let fork_schedule = ForkSchedule::new(&network, Some(100), Some(200), Some(300));
+        let blob_schedule = BlobSchedule::new(&network, &fork_schedule, None);
+
+        assert_eq!(blob_schedule.parameters_at(150), &DENEB_BLOB_PARAMETERS);
+        assert_eq!(blob_schedule.parameters_at(200), &ELECTRA_BLOB_PARAMETERS);
+    }
+
+    #[test]
+    fn uses_custom_bpo_schedule() {
+        let network = Network::Devnet;
+        let fork_schedule = ForkSchedule::new(&network, Some(0), Some(100), Some(200));
+        let blob_schedule = BlobSchedule::new(
+            &network,
+            &fork_schedule,
+            Some(&bpo_schedule("200:10:15:8346193, 300:14:21:11684671")),
+        );
+
+        assert_eq!(blob_schedule.parameters_at(50), &DENEB_BLOB_PARAMETERS);
+        assert_eq!(blob_schedule.parameters_at(150), &ELECTRA_BLOB_PARAMETERS);
+        // BPO forks take precedence over forks activated at the same slot
+        assert_eq!(blob_schedule.parameters_at(200), &BPO1_BLOB_PARAMETERS);
+        assert_eq!(blob_schedule.parameters_at(300), &BPO2_BLOB_PARAMETERS);
+    }
+
+    #[test]
+    fn keeps_network_min_blob_base_fee_in_custom_bpo_schedule() {
+        let network = Network::Gnosis;
+        let fork_schedule = ForkSchedule::new(&network, None, None, None);
+        let blob_schedule = BlobSchedule::new(
+            &network,
+            &fork_schedule,
+            Some(&bpo_schedule("50000000:2:4:2225652")),
+        );
+        let parameters = blob_schedule.parameters_at(50000000);
+
+        assert_eq!(parameters.max_blobs_per_block, 4);
+        assert_eq!(
+            parameters.min_base_fee_per_blob_gas,
+            GNOSIS_BLOB_PARAMETERS.min_base_fee_per_blob_gas
+        );
+    }
+
+    #[test]
+    fn parses_bpo_schedules() {
+        let schedule = bpo_schedule("200:10:15:8346193,300:14:21:11684671");
+
+        assert_eq!(
+            String::from(schedule),
+            "200:10:15:8346193,300:14:21:11684671"
+        );
+        assert_eq!(bpo_schedule(""), BpoSchedule(vec![]));
+        assert!(BpoSchedule::try_from("200:10:15".to_string()).is_err());
+        assert!(BpoSchedule::try_from("200:10:15:x".to_string()).is_err());
+    }
+}
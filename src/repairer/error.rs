@@ -0,0 +1,7 @@
+use crate::clients::beacon::types::BlockIdResolutionError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RepairerError {
+    #[error(transparent)]
+    FailedBlockIdResolution(#[from] BlockIdResolutionError),
+}
@@ -0,0 +1,192 @@
+use std::fmt;
+
+use alloy::transports::Transport;
+use futures::{stream, StreamExt};
+use tracing::{debug, info, warn};
+
+use crate::{
+    clients::beacon::types::{BlockId, BlockIdResolution},
+    context::CommonContext,
+    slots_processor::SlotsProcessor,
+};
+
+use self::error::RepairerError;
+
+pub mod error;
+
+/// Walks a slot range looking for blocks with blobs that are missing from the sink and
+/// re-indexes them.
+pub struct Repairer<T> {
+    context: Box<dyn CommonContext<T>>,
+    num_threads: u32,
+}
+
+#[derive(Debug)]
+enum SlotStatus {
+    WithoutBlobs,
+    Indexed,
+    Repaired,
+    Failed(String),
+}
+
+#[derive(Debug, Default)]
+pub struct RepairSummary {
+    pub initial_slot: u32,
+    pub final_slot: u32,
+    pub checked_slots: u32,
+    pub slots_with_blobs: u32,
+    pub repaired_slots: Vec<u32>,
+    pub failed_slots: Vec<(u32, String)>,
+}
+
+impl<T> Repairer<T>
+where
+    T: Transport + Clone + Send + Sync + 'static,
+{
+    pub fn new(context: Box<dyn CommonContext<T>>, num_threads: u32) -> Self {
+        Self {
+            context,
+            num_threads,
+        }
+    }
+
+    /// Repairs every slot between the given blocks, both included.
+    pub async fn repair(
+        &self,
+        initial_block_id: BlockId,
+        final_block_id: BlockId,
+    ) -> Result<RepairSummary, RepairerError> {
+        let beacon_client = self.context.beacon_client();
+        let initial_slot = initial_block_id.resolve_to_slot(beacon_client).await?;
+        let final_slot = final_block_id.resolve_to_slot(beacon_client).await?;
+        let (initial_slot, final_slot) = if initial_slot <= final_slot {
+            (initial_slot, final_slot)
+        } else {
+            (final_slot, initial_slot)
+        };
+
+        info!(initial_slot, final_slot, "Repairing slots…");
+
+        let slots_processor = SlotsProcessor::new(self.context.clone(), None);
+        let statuses = stream::iter(initial_slot..=final_slot)
+            .map(|slot| {
+                let slots_processor = &slots_processor;
+
+                async move { (slot, self.repair_slot(slots_processor, slot).await) }
+            })
+            .buffered(self.num_threads.max(1) as usize)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut summary = RepairSummary {
+            initial_slot,
+            final_slot,
+            ..Default::default()
+        };
+
+        for (slot, status) in statuses {
+            summary.checked_slots += 1;
+
+            match status {
+                SlotStatus::WithoutBlobs => {}
+                SlotStatus::Indexed => summary.slots_with_blobs += 1,
+                SlotStatus::Repaired => {
+                    summary.slots_with_blobs += 1;
+                    summary.repaired_slots.push(slot);
+                }
+                SlotStatus::Failed(error) => {
+                    summary.slots_with_blobs += 1;
+                    summary.failed_slots.push((slot, error));
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn repair_slot(&self, slots_processor: &SlotsProcessor<T>, slot: u32) -> SlotStatus {
+        let beacon_client = self.context.beacon_client();
+
+        let block_header = match beacon_client.get_block_header(slot.into()).await {
+            Ok(Some(header)) => header,
+            Ok(None) => {
+                debug!(slot, "Skipping as there is no beacon block header");
+
+                return SlotStatus::WithoutBlobs;
+            }
+            Err(error) => return SlotStatus::Failed(error.to_string()),
+        };
+
+        let beacon_block = match beacon_client.get_block(slot.into()).await {
+            Ok(Some(block)) => block,
+            Ok(None) => return SlotStatus::WithoutBlobs,
+            Err(error) => return SlotStatus::Failed(error.to_string()),
+        };
+
+        let has_kzg_blob_commitments = beacon_block
+            .blob_kzg_commitments
+            .is_some_and(|commitments| !commitments.is_empty());
+
+        let execution_block_hash = match beacon_block.execution_payload {
+            Some(payload) if has_kzg_blob_commitments => payload.block_hash,
+            _ => return SlotStatus::WithoutBlobs,
+        };
+
+        match self.context.sink().get_block(slot).await {
+            Ok(Some(block)) if block.hash == execution_block_hash => return SlotStatus::Indexed,
+            Ok(Some(block)) => {
+                warn!(
+                    slot,
+                    indexed_block_hash = ?block.hash,
+                    canonical_block_hash = ?execution_block_hash,
+                    "Indexed block doesn't match the canonical one"
+                );
+            }
+            Ok(None) => {}
+            Err(error) => return SlotStatus::Failed(error.to_string()),
+        }
+
+        match slots_processor.process_block(&block_header).await {
+            Ok(_) => {
+                info!(slot, "Slot repaired");
+
+                SlotStatus::Repaired
+            }
+            Err(error) => {
+                warn!(slot, ?error, "Failed to repair slot");
+
+                SlotStatus::Failed(error.to_string())
+            }
+        }
+    }
+}
+
+impl fmt::Display for RepairSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Repair summary for slots {}-{}:",
+            self.initial_slot, self.final_slot
+        )?;
+        writeln!(f, "- Checked slots: {}", self.checked_slots)?;
+        writeln!(f, "- Slots with blobs: {}", self.slots_with_blobs)?;
+        writeln!(
+            f,
+            "- Already indexed: {}",
+            self.slots_with_blobs as usize - self.repaired_slots.len() - self.failed_slots.len()
+        )?;
+        writeln!(f, "- Repaired: {}", self.repaired_slots.len())?;
+
+        for slot in self.repaired_slots.iter() {
+            writeln!(f, "  - {}", slot)?;
+        }
+
+        writeln!(f, "- Failed: {}", self.failed_slots.len())?;
+
+        for (slot, error) in self.failed_slots.iter() {
+            writeln!(f, "  - {}: {}", slot, error)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+use crate::clients::common::ClientError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetrierError {
+    #[error("failed to retrieve the failed slots chunks")]
+    FailedChunksRetrieval(#[source] ClientError),
+    #[error("failed slots chunks not found: {0:?}")]
+    ChunksNotFound(Vec<u32>),
+}
@@ -0,0 +1,288 @@
+use std::fmt;
+
+use alloy::transports::Transport;
+use tracing::{info, warn};
+
+use crate::{
+    clients::blobscan::types::FailedSlotsChunk, context::CommonContext, metrics,
+    slots_processor::SlotsProcessor,
+};
+
+use self::error::RetrierError;
+
+pub mod error;
+
+/// Retries the slot ranges queued in the sink after failing to be indexed, removing them from
+/// the queue once they're indexed.
+pub struct Retrier<T> {
+    context: Box<dyn CommonContext<T>>,
+}
+
+#[derive(Debug, Default)]
+pub struct RetrySummary {
+    pub retried_chunks: Vec<FailedSlotsChunk>,
+    pub failed_chunks: Vec<(FailedSlotsChunk, String)>,
+}
+
+impl<T> Retrier<T>
+where
+    T: Transport + Clone + Send + Sync + 'static,
+{
+    pub fn new(context: Box<dyn CommonContext<T>>) -> Self {
+        Self { context }
+    }
+
+    pub async fn get_failed_chunks(&self) -> Result<Vec<FailedSlotsChunk>, RetrierError> {
+        self.context
+            .sink()
+            .get_failed_slots_chunks()
+            .await
+            .map_err(RetrierError::FailedChunksRetrieval)
+    }
+
+    /// Retries the queued chunks with the given ids, or all of them if no id is given.
+    pub async fn retry(&self, chunk_ids: &[u32]) -> Result<RetrySummary, RetrierError> {
+        let mut chunks = self.get_failed_chunks().await?;
+
+        if !chunk_ids.is_empty() {
+            let missing_chunk_ids = chunk_ids
+                .iter()
+                .filter(|id| !chunks.iter().any(|chunk| chunk.id == Some(**id)))
+                .copied()
+                .collect::<Vec<_>>();
+
+            if !missing_chunk_ids.is_empty() {
+                return Err(RetrierError::ChunksNotFound(missing_chunk_ids));
+            }
+
+            chunks.retain(|chunk| chunk.id.is_some_and(|id| chunk_ids.contains(&id)));
+        }
+
+        let mut summary = RetrySummary::default();
+
+        for chunk in chunks {
+            match self.retry_chunk(&chunk).await {
+                Ok(_) => {
+                    info!(%chunk, "Failed slots chunk indexed");
+
+                    metrics::RETRIED_SLOTS_CHUNKS
+                        .with_label_values(&["success"])
+                        .inc();
+
+                    summary.retried_chunks.push(chunk);
+                }
+                Err(error) => {
+                    warn!(%chunk, error, "Failed to retry failed slots chunk");
+
+                    metrics::RETRIED_SLOTS_CHUNKS
+                        .with_label_values(&["failure"])
+                        .inc();
+
+                    summary.failed_chunks.push((chunk, error));
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn retry_chunk(&self, chunk: &FailedSlotsChunk) -> Result<(), String> {
+        let mut slots_processor = SlotsProcessor::new(self.context.clone(), None);
+
+        slots_processor
+            .process_slots(chunk.initial_slot, chunk.final_slot)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        if let Some(id) = chunk.id {
+            self.context
+                .sink()
+                .remove_failed_slots_chunks(vec![id])
+                .await
+                .map_err(|error| format!("Failed to remove it from the queue: {error}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl RetrySummary {
+    pub fn is_empty(&self) -> bool {
+        self.retried_chunks.is_empty() && self.failed_chunks.is_empty()
+    }
+}
+
+impl fmt::Display for RetrySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Retry summary for failed slots chunks:")?;
+        writeln!(f, "- Indexed: {}", self.retried_chunks.len())?;
+
+        for chunk in self.retried_chunks.iter() {
+            writeln!(f, "  - {}", chunk)?;
+        }
+
+        writeln!(f, "- Failed: {}", self.failed_chunks.len())?;
+
+        for (chunk, error) in self.failed_chunks.iter() {
+            writeln!(f, "  - {}: {}", chunk, error)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::transports::BoxTransport;
+    use anyhow::anyhow;
+    use mockall::predicate::eq;
+
+    use crate::{
+        clients::{
+            beacon::{types::BlockId, MockCommonBeaconClient},
+            common::ClientError,
+        },
+        context::SinkContext,
+        sinks::MockSink,
+    };
+
+    use super::*;
+
+    fn failed_chunk(id: u32, initial_slot: u32, final_slot: u32) -> FailedSlotsChunk {
+        FailedSlotsChunk {
+            id: Some(id),
+            initial_slot,
+            final_slot,
+        }
+    }
+
+    /// Creates a retrier whose sink queues the given chunks. Every slot is empty except the
+    /// failing one, whose block header can't be fetched.
+    fn create_retrier(
+        chunks: Vec<FailedSlotsChunk>,
+        failing_slot: Option<u32>,
+        mut sink: MockSink,
+    ) -> Retrier<BoxTransport> {
+        let mut beacon_client = MockCommonBeaconClient::new();
+
+        sink.expect_get_failed_slots_chunks().returning(move || {
+            let chunks = chunks.clone();
+
+            Box::pin(async move { Ok(chunks) })
+        });
+        beacon_client
+            .expect_get_block_header()
+            .returning(move |block_id| {
+                let result = match block_id {
+                    BlockId::Slot(slot) if Some(slot) == failing_slot => {
+                        Err(ClientError::Other(anyhow!("Beacon node unavailable")))
+                    }
+                    _ => Ok(None),
+                };
+
+                Box::pin(async move { result })
+            });
+
+        let context = SinkContext::new(sink, None).with_beacon_client(beacon_client);
+
+        Retrier::new(Box::new(context))
+    }
+
+    #[tokio::test]
+    async fn fails_on_unknown_chunk_ids() {
+        let retrier = create_retrier(vec![failed_chunk(1, 0, 10)], None, MockSink::new());
+
+        let result = retrier.retry(&[1, 2, 3]).await;
+
+        assert!(matches!(result, Err(RetrierError::ChunksNotFound(ids)) if ids == vec![2, 3]));
+    }
+
+    #[tokio::test]
+    async fn removes_only_indexed_chunks_from_queue() {
+        let mut sink = MockSink::new();
+
+        sink.expect_remove_failed_slots_chunks()
+            .with(eq(vec![1]))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        sink.expect_remove_failed_slots_chunks()
+            .with(eq(vec![3]))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let retrier = create_retrier(
+            vec![
+                failed_chunk(1, 0, 10),
+                failed_chunk(2, 10, 20),
+                failed_chunk(3, 20, 30),
+            ],
+            Some(15),
+            sink,
+        );
+
+        let summary = retrier.retry(&[]).await.unwrap();
+
+        assert_eq!(
+            summary.retried_chunks,
+            vec![failed_chunk(1, 0, 10), failed_chunk(3, 20, 30)]
+        );
+        assert_eq!(summary.failed_chunks.len(), 1);
+        assert_eq!(summary.failed_chunks[0].0, failed_chunk(2, 10, 20));
+    }
+
+    #[tokio::test]
+    async fn retries_only_the_given_chunks() {
+        let mut sink = MockSink::new();
+
+        sink.expect_remove_failed_slots_chunks()
+            .with(eq(vec![2]))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        let retrier = create_retrier(
+            vec![failed_chunk(1, 0, 10), failed_chunk(2, 10, 20)],
+            Some(5),
+            sink,
+        );
+
+        let summary = retrier.retry(&[2]).await.unwrap();
+
+        assert_eq!(summary.retried_chunks, vec![failed_chunk(2, 10, 20)]);
+        assert!(summary.failed_chunks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn counts_chunks_that_fail_to_leave_the_queue_as_failed() {
+        let mut sink = MockSink::new();
+
+        sink.expect_remove_failed_slots_chunks().returning(|_| {
+            Box::pin(async { Err(ClientError::Other(anyhow!("Sink unavailable"))) })
+        });
+
+        let retrier = create_retrier(vec![failed_chunk(1, 0, 10)], None, sink);
+
+        let summary = retrier.retry(&[]).await.unwrap();
+
+        assert!(summary.retried_chunks.is_empty());
+        assert_eq!(summary.failed_chunks.len(), 1);
+        assert!(summary.failed_chunks[0]
+            .1
+            .starts_with("Failed to remove it from the queue"));
+    }
+
+    #[test]
+    fn summarizes_retried_and_failed_chunks() {
+        let summary = RetrySummary {
+            retried_chunks: vec![failed_chunk(1, 0, 10), failed_chunk(3, 20, 30)],
+            failed_chunks: vec![(failed_chunk(2, 10, 20), "Slot 15 failed".to_string())],
+        };
+
+        let output = summary.to_string();
+
+        assert!(!summary.is_empty());
+        assert!(output.contains("- Indexed: 2\n"));
+        assert!(output.contains("- Failed: 1\n"));
+        assert!(output.contains(": Slot 15 failed\n"));
+        assert!(RetrySummary::default().is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use alloy::primitives::{address, Address};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::network::Network;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionCategory {
+    Rollup,
+    Other,
+}
+
+impl TransactionCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionCategory::Rollup => "rollup",
+            TransactionCategory::Other => "other",
+        }
+    }
+}
+
+/// Attributes blob transactions to the rollups posting them, keyed by sender address.
+///
+/// The built-in mapping of the network can be extended or overridden by a TOML file mapping
+/// addresses to rollup names, which is read again on every reload.
+pub struct RollupRegistry {
+    network: Network,
+    path: Option<PathBuf>,
+    rollups: RwLock<HashMap<Address, String>>,
+}
+
+impl RollupRegistry {
+    pub fn try_new(network: Network, path: Option<&Path>) -> Result<Self, anyhow::Error> {
+        let rollups = build_rollups(&network, path)?;
+
+        Ok(Self {
+            network,
+            path: path.map(Path::to_path_buf),
+            rollups: RwLock::new(rollups),
+        })
+    }
+
+    /// Returns the rollup that posted a transaction sent from the given address, if known.
+    pub fn get_rollup(&self, from: &Address) -> Option<String> {
+        self.rollups.read().unwrap().get(from).cloned()
+    }
+
+    /// Rebuilds the mapping from the built-in defaults and the registry file. The current mapping
+    /// is kept if the file can't be read.
+    pub fn reload(&self) -> Result<(), anyhow::Error> {
+        let rollups = build_rollups(&self.network, self.path.as_deref())?;
+        let total_addresses = rollups.len();
+
+        *self.rollups.write().unwrap() = rollups;
+
+        info!(total_addresses, "Rollup registry reloaded");
+
+        Ok(())
+    }
+}
+
+fn build_rollups(
+    network: &Network,
+    path: Option<&Path>,
+) -> Result<HashMap<Address, String>, anyhow::Error> {
+    let mut rollups = default_rollups(network)
+        .iter()
+        .map(|(address, rollup)| (*address, rollup.to_string()))
+        .collect::<HashMap<_, _>>();
+
+    if let Some(path) = path {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read rollup registry {}", path.display()))?;
+        let overrides = toml::from_str::<HashMap<Address, String>>(&contents)
+            .with_context(|| format!("Invalid rollup registry {}", path.display()))?;
+
+        rollups.extend(overrides);
+    }
+
+    Ok(rollups)
+}
+
+const MAINNET_ROLLUPS: &[(Address, &str)] = &[
+    (
+        address!("c1b634853cb333d3ad8663715b08f41a3aec47cc"),
+        "arbitrum",
+    ),
+    (address!("5050f69a9786f081509234f1a7f4684b5e5b76c9"), "base"),
+    (
+        address!("a9268341831efa4937537bc3e9eb36dbece83c7e"),
+        "linea",
+    ),
+    (
+        address!("6887246668a3b87f54deb3b94ba47a6f63f32985"),
+        "optimism",
+    ),
+    (
+        address!("cf2898225ed05be911d3709d9417e86e0b4cfc8f"),
+        "scroll",
+    ),
+    (
+        address!("2c169dfe5fbba12957bdd0ba47d9cedbfe260ca7"),
+        "starknet",
+    ),
+    (
+        address!("0d3250c3d5facb74ac15834096397a3ef790ec99"),
+        "zksync",
+    ),
+    (address!("625726c858dbf78c0125436c943bf4b4be9d9033"), "zora"),
+];
+
+const SEPOLIA_ROLLUPS: &[(Address, &str)] = &[
+    (address!("6cdebe940bc0f26850285caca097c11c33103e47"), "base"),
+    (
+        address!("8f23bb38f531600e5d8fddaaec41f13fab46e98c"),
+        "optimism",
+    ),
+];
+
+fn default_rollups(network: &Network) -> &'static [(Address, &'static str)] {
+    match network {
+        Network::Mainnet => MAINNET_ROLLUPS,
+        Network::Sepolia => SEPOLIA_ROLLUPS,
+        _ => &[],
+    }
+}
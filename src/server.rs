@@ -0,0 +1,56 @@
+use std::{io, net::SocketAddr};
+
+use axum::{http::StatusCode, routing::get, Json, Router};
+use tokio::{net::TcpListener, task::JoinHandle};
+use tracing::{error, info};
+
+use crate::{
+    health::{HealthReport, HEALTH},
+    metrics,
+};
+
+/// Binds the HTTP server exposing the indexer's operational endpoints and spawns it, failing if
+/// the address can't be bound.
+pub async fn spawn(address: SocketAddr) -> io::Result<JoinHandle<()>> {
+    metrics::register();
+
+    let router = Router::new()
+        .route("/metrics", get(get_metrics))
+        .route("/healthz", get(get_health))
+        .route("/readyz", get(get_readiness));
+    let listener = TcpListener::bind(address).await?;
+
+    info!(%address, "HTTP server listening");
+
+    Ok(tokio::spawn(async move {
+        if let Err(error) = axum::serve(listener, router).await {
+            error!(?error, "HTTP server stopped unexpectedly");
+        }
+    }))
+}
+
+async fn get_metrics() -> Result<String, (StatusCode, String)> {
+    metrics::encode().map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()))
+}
+
+async fn get_health() -> (StatusCode, Json<HealthReport>) {
+    let report = HEALTH.report();
+    let status = if report.healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(report))
+}
+
+async fn get_readiness() -> (StatusCode, Json<HealthReport>) {
+    let report = HEALTH.report();
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(report))
+}
@@ -0,0 +1,63 @@
+use alloy::primitives::B256;
+use async_trait::async_trait;
+
+use crate::clients::{
+    blobscan::{
+        types::{
+            Blob, BlobscanBlock, Block, BlockchainSyncState, FailedSlotsChunk, IndexRequest,
+            Transaction,
+        },
+        BlobscanClient, CommonBlobscanClient,
+    },
+    common::ClientResult,
+};
+
+use super::Sink;
+
+#[async_trait]
+impl Sink for BlobscanClient {
+    async fn index(
+        &self,
+        block: Block,
+        transactions: Vec<Transaction>,
+        blobs: Vec<Blob>,
+    ) -> ClientResult<()> {
+        CommonBlobscanClient::index(self, block, transactions, blobs).await
+    }
+
+    async fn index_batch(&self, requests: Vec<IndexRequest>) -> ClientResult<()> {
+        CommonBlobscanClient::index_batch(self, requests).await
+    }
+
+    async fn get_block(&self, slot: u32) -> ClientResult<Option<BlobscanBlock>> {
+        CommonBlobscanClient::get_block(self, slot).await
+    }
+
+    async fn handle_reorg(
+        &self,
+        rewinded_blocks: Vec<B256>,
+        forwarded_blocks: Vec<B256>,
+    ) -> ClientResult<()> {
+        CommonBlobscanClient::handle_reorg(self, rewinded_blocks, forwarded_blocks).await
+    }
+
+    async fn update_sync_state(&self, sync_state: BlockchainSyncState) -> ClientResult<()> {
+        CommonBlobscanClient::update_sync_state(self, sync_state).await
+    }
+
+    async fn get_sync_state(&self) -> ClientResult<Option<BlockchainSyncState>> {
+        CommonBlobscanClient::get_sync_state(self).await
+    }
+
+    async fn add_failed_slots_chunks(&self, chunks: Vec<FailedSlotsChunk>) -> ClientResult<()> {
+        CommonBlobscanClient::add_failed_slots_chunks(self, chunks).await
+    }
+
+    async fn get_failed_slots_chunks(&self) -> ClientResult<Vec<FailedSlotsChunk>> {
+        CommonBlobscanClient::get_failed_slots_chunks(self).await
+    }
+
+    async fn remove_failed_slots_chunks(&self, chunk_ids: Vec<u32>) -> ClientResult<()> {
+        CommonBlobscanClient::remove_failed_slots_chunks(self, chunk_ids).await
+    }
+}
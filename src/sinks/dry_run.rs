@@ -0,0 +1,162 @@
+use std::{fs, path::Path};
+
+use alloy::primitives::B256;
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+use tracing::info;
+
+use crate::clients::{
+    blobscan::types::{
+        Blob, BlobscanBlock, Block, BlockchainSyncState, FailedSlotsChunk,
+        FailedSlotsChunksRequest, IndexRequest, RemoveFailedSlotsChunksRequest,
+        ReorgedBlocksRequestBody, Transaction,
+    },
+    common::ClientResult,
+};
+
+use super::Sink;
+
+/// Sink that logs the write operations instead of sending them to the wrapped sink, optionally
+/// recording them in a JSON Lines file. Reads are still served by the wrapped sink.
+#[derive(Debug)]
+pub struct DryRunSink {
+    inner: Box<dyn Sink>,
+    output: Option<Mutex<tokio::fs::File>>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Record {
+    Index(IndexRequest),
+    Reorg(ReorgedBlocksRequestBody),
+    SyncState(BlockchainSyncState),
+    FailedSlotsChunks(FailedSlotsChunksRequest),
+    RemoveFailedSlotsChunks(RemoveFailedSlotsChunksRequest),
+}
+
+impl DryRunSink {
+    pub fn try_new(
+        inner: Box<dyn Sink>,
+        output_path: Option<&Path>,
+    ) -> Result<Self, anyhow::Error> {
+        let output = match output_path {
+            Some(path) => {
+                let file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| {
+                        format!("Failed to open dry run output file {}", path.display())
+                    })?;
+
+                Some(Mutex::new(tokio::fs::File::from_std(file)))
+            }
+            None => None,
+        };
+
+        Ok(Self { inner, output })
+    }
+
+    async fn record(&self, record: &Record) -> ClientResult<()> {
+        if let Some(output) = &self.output {
+            let mut line = serde_json::to_string(record)?;
+
+            line.push('\n');
+
+            output
+                .lock()
+                .await
+                .write_all(line.as_bytes())
+                .await
+                .map_err(|err| anyhow!(err))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Sink for DryRunSink {
+    async fn index(
+        &self,
+        block: Block,
+        transactions: Vec<Transaction>,
+        mut blobs: Vec<Blob>,
+    ) -> ClientResult<()> {
+        info!(
+            slot = block.slot,
+            block_number = block.number,
+            transactions = transactions.len(),
+            blobs = blobs.len(),
+            "Dry run: skipping block indexing"
+        );
+
+        // Blob data is left out to keep the records small
+        for blob in blobs.iter_mut() {
+            blob.data = None;
+        }
+
+        self.record(&Record::Index(IndexRequest {
+            block,
+            transactions,
+            blobs,
+        }))
+        .await
+    }
+
+    async fn get_block(&self, slot: u32) -> ClientResult<Option<BlobscanBlock>> {
+        self.inner.get_block(slot).await
+    }
+
+    async fn handle_reorg(
+        &self,
+        rewinded_blocks: Vec<B256>,
+        forwarded_blocks: Vec<B256>,
+    ) -> ClientResult<()> {
+        info!(
+            ?rewinded_blocks,
+            ?forwarded_blocks,
+            "Dry run: skipping reorg handling"
+        );
+
+        self.record(&Record::Reorg(ReorgedBlocksRequestBody {
+            forwarded_blocks,
+            rewinded_blocks,
+        }))
+        .await
+    }
+
+    async fn update_sync_state(&self, sync_state: BlockchainSyncState) -> ClientResult<()> {
+        info!(?sync_state, "Dry run: skipping sync state update");
+
+        self.record(&Record::SyncState(sync_state)).await
+    }
+
+    async fn get_sync_state(&self) -> ClientResult<Option<BlockchainSyncState>> {
+        self.inner.get_sync_state().await
+    }
+
+    async fn add_failed_slots_chunks(&self, chunks: Vec<FailedSlotsChunk>) -> ClientResult<()> {
+        info!(?chunks, "Dry run: skipping failed slots chunks queueing");
+
+        self.record(&Record::FailedSlotsChunks(FailedSlotsChunksRequest {
+            chunks,
+        }))
+        .await
+    }
+
+    async fn get_failed_slots_chunks(&self) -> ClientResult<Vec<FailedSlotsChunk>> {
+        self.inner.get_failed_slots_chunks().await
+    }
+
+    async fn remove_failed_slots_chunks(&self, chunk_ids: Vec<u32>) -> ClientResult<()> {
+        info!(?chunk_ids, "Dry run: skipping failed slots chunks removal");
+
+        self.record(&Record::RemoveFailedSlotsChunks(
+            RemoveFailedSlotsChunksRequest { chunk_ids },
+        ))
+        .await
+    }
+}
@@ -0,0 +1,420 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use alloy::primitives::B256;
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+use crate::clients::{
+    blobscan::types::{
+        Blob, BlobscanBlock, Block, BlockchainSyncState, FailedSlotsChunk, IndexRequest,
+        ReorgedBlocksRequestBody, Transaction,
+    },
+    common::ClientResult,
+};
+
+use super::Sink;
+
+const RECORDS_FILE_NAME: &str = "records.jsonl";
+const SYNC_STATE_FILE_NAME: &str = "sync-state.json";
+const FAILED_SLOTS_CHUNKS_FILE_NAME: &str = "failed-slots-chunks.json";
+
+/// Sink that appends every indexing operation to a JSON Lines file in a local directory.
+#[derive(Debug)]
+pub struct FileSink {
+    records_path: PathBuf,
+    sync_state_path: PathBuf,
+    failed_slots_chunks_path: PathBuf,
+    state: Mutex<FileSinkState>,
+}
+
+#[derive(Debug, Default)]
+struct FileSinkState {
+    blocks_by_slot: HashMap<u32, BlobscanBlock>,
+    sync_state: Option<BlockchainSyncState>,
+    failed_slots_chunks: Vec<FailedSlotsChunk>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Record {
+    Index(IndexRequest),
+    Reorg(ReorgedBlocksRequestBody),
+}
+
+/// Fields of a record needed to track the indexed blocks. Records are loaded through it so
+/// their transactions and blobs are skipped instead of being read into memory.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RecordSummary {
+    #[serde(rename = "type")]
+    kind: RecordKind,
+    block: Option<Block>,
+    #[serde(default)]
+    rewinded_blocks: Vec<B256>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+enum RecordKind {
+    Index,
+    Reorg,
+}
+
+impl FileSink {
+    pub fn try_new(dir: &Path) -> Result<Self, anyhow::Error> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create sink directory {}", dir.display()))?;
+
+        Self::try_open(dir)
+    }
+
+    /// Opens an existing directory without creating it, for read-only use (e.g. dry runs),
+    /// keeping only the blocks of its records in memory. A missing directory is treated as
+    /// empty.
+    pub fn try_open(dir: &Path) -> Result<Self, anyhow::Error> {
+        let records_path = dir.join(RECORDS_FILE_NAME);
+        let sync_state_path = dir.join(SYNC_STATE_FILE_NAME);
+        let failed_slots_chunks_path = dir.join(FAILED_SLOTS_CHUNKS_FILE_NAME);
+        let mut state = FileSinkState::default();
+
+        if records_path.exists() {
+            let file = fs::File::open(&records_path)?;
+
+            for (i, line) in BufReader::new(file).lines().enumerate() {
+                let line_context = || {
+                    format!(
+                        "Invalid record at line {} of {}",
+                        i + 1,
+                        records_path.display()
+                    )
+                };
+                let record =
+                    serde_json::from_str::<RecordSummary>(&line?).with_context(line_context)?;
+
+                match (record.kind, record.block) {
+                    (RecordKind::Index, Some(block)) => state.index_block(&block),
+                    (RecordKind::Index, None) => {
+                        return Err(anyhow!("Missing block").context(line_context()))
+                    }
+                    (RecordKind::Reorg, _) => state.rewind_blocks(&record.rewinded_blocks),
+                }
+            }
+        }
+
+        if sync_state_path.exists() {
+            let sync_state = fs::read_to_string(&sync_state_path)?;
+
+            state.sync_state = Some(serde_json::from_str(&sync_state)?);
+        }
+
+        if failed_slots_chunks_path.exists() {
+            let failed_slots_chunks = fs::read_to_string(&failed_slots_chunks_path)?;
+
+            state.failed_slots_chunks = serde_json::from_str(&failed_slots_chunks)?;
+        }
+
+        Ok(Self {
+            records_path,
+            sync_state_path,
+            failed_slots_chunks_path,
+            state: Mutex::new(state),
+        })
+    }
+
+    async fn append(&self, record: &Record) -> ClientResult<()> {
+        let mut line = serde_json::to_string(record)?;
+
+        line.push('\n');
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.records_path)
+            .await
+            .map_err(|err| anyhow!(err))?;
+
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|err| anyhow!(err))?;
+
+        Ok(())
+    }
+}
+
+/// Replaces a file through a temporary one so it's never left half written.
+async fn write_file(path: &Path, contents: Vec<u8>) -> ClientResult<()> {
+    let tmp_path = path.with_extension("json.tmp");
+
+    tokio::fs::write(&tmp_path, contents)
+        .await
+        .map_err(|err| anyhow!(err))?;
+    tokio::fs::rename(&tmp_path, path)
+        .await
+        .map_err(|err| anyhow!(err))?;
+
+    Ok(())
+}
+
+impl FileSinkState {
+    fn apply(&mut self, record: &Record) {
+        match record {
+            Record::Index(req) => self.index_block(&req.block),
+            Record::Reorg(req) => self.rewind_blocks(&req.rewinded_blocks),
+        }
+    }
+
+    fn index_block(&mut self, block: &Block) {
+        self.blocks_by_slot.insert(
+            block.slot,
+            BlobscanBlock {
+                hash: block.hash,
+                number: block.number as u32,
+                slot: block.slot,
+            },
+        );
+    }
+
+    fn rewind_blocks(&mut self, rewinded_blocks: &[B256]) {
+        self.blocks_by_slot
+            .retain(|_, block| !rewinded_blocks.contains(&block.hash));
+    }
+}
+
+#[async_trait]
+impl Sink for FileSink {
+    async fn index(
+        &self,
+        block: Block,
+        transactions: Vec<Transaction>,
+        blobs: Vec<Blob>,
+    ) -> ClientResult<()> {
+        let record = Record::Index(IndexRequest {
+            block,
+            transactions,
+            blobs,
+        });
+        let mut state = self.state.lock().await;
+
+        self.append(&record).await?;
+
+        state.apply(&record);
+
+        Ok(())
+    }
+
+    async fn get_block(&self, slot: u32) -> ClientResult<Option<BlobscanBlock>> {
+        let state = self.state.lock().await;
+
+        Ok(state.blocks_by_slot.get(&slot).cloned())
+    }
+
+    async fn handle_reorg(
+        &self,
+        rewinded_blocks: Vec<B256>,
+        forwarded_blocks: Vec<B256>,
+    ) -> ClientResult<()> {
+        let record = Record::Reorg(ReorgedBlocksRequestBody {
+            forwarded_blocks,
+            rewinded_blocks,
+        });
+        let mut state = self.state.lock().await;
+
+        self.append(&record).await?;
+
+        state.apply(&record);
+
+        Ok(())
+    }
+
+    async fn update_sync_state(&self, sync_state: BlockchainSyncState) -> ClientResult<()> {
+        let mut state = self.state.lock().await;
+        let current = state.sync_state.clone().unwrap_or_default();
+
+        // Only the provided fields are updated, as the Blobscan API does
+        let new_sync_state = BlockchainSyncState {
+            last_finalized_block: sync_state
+                .last_finalized_block
+                .or(current.last_finalized_block),
+            last_lower_synced_slot: sync_state
+                .last_lower_synced_slot
+                .or(current.last_lower_synced_slot),
+            last_upper_synced_slot: sync_state
+                .last_upper_synced_slot
+                .or(current.last_upper_synced_slot),
+            last_upper_synced_block_root: sync_state
+                .last_upper_synced_block_root
+                .or(current.last_upper_synced_block_root),
+            last_upper_synced_block_slot: sync_state
+                .last_upper_synced_block_slot
+                .or(current.last_upper_synced_block_slot),
+        };
+
+        write_file(&self.sync_state_path, serde_json::to_vec(&new_sync_state)?).await?;
+
+        state.sync_state = Some(new_sync_state);
+
+        Ok(())
+    }
+
+    async fn get_sync_state(&self) -> ClientResult<Option<BlockchainSyncState>> {
+        let state = self.state.lock().await;
+
+        Ok(state.sync_state.clone())
+    }
+
+    async fn add_failed_slots_chunks(&self, chunks: Vec<FailedSlotsChunk>) -> ClientResult<()> {
+        let mut state = self.state.lock().await;
+        let mut failed_slots_chunks = state.failed_slots_chunks.clone();
+        let next_id = failed_slots_chunks
+            .iter()
+            .filter_map(|chunk| chunk.id)
+            .max()
+            .map_or(1, |id| id + 1);
+
+        failed_slots_chunks.extend(chunks.into_iter().zip(next_id..).map(|(chunk, id)| {
+            FailedSlotsChunk {
+                id: Some(id),
+                ..chunk
+            }
+        }));
+
+        write_file(
+            &self.failed_slots_chunks_path,
+            serde_json::to_vec(&failed_slots_chunks)?,
+        )
+        .await?;
+
+        state.failed_slots_chunks = failed_slots_chunks;
+
+        Ok(())
+    }
+
+    async fn get_failed_slots_chunks(&self) -> ClientResult<Vec<FailedSlotsChunk>> {
+        let state = self.state.lock().await;
+
+        Ok(state.failed_slots_chunks.clone())
+    }
+
+    async fn remove_failed_slots_chunks(&self, chunk_ids: Vec<u32>) -> ClientResult<()> {
+        let mut state = self.state.lock().await;
+        let failed_slots_chunks = state
+            .failed_slots_chunks
+            .iter()
+            .filter(|chunk| !chunk.id.is_some_and(|id| chunk_ids.contains(&id)))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        write_file(
+            &self.failed_slots_chunks_path,
+            serde_json::to_vec(&failed_slots_chunks)?,
+        )
+        .await?;
+
+        state.failed_slots_chunks = failed_slots_chunks;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+
+    use super::*;
+
+    /// Returns a fresh sink directory in the temporary directory, named after the test using it.
+    fn sink_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("blob-indexer-file-sink-{name}"));
+
+        let _ = fs::remove_dir_all(&dir);
+
+        dir
+    }
+
+    fn block(slot: u32, hash_byte: u8) -> Block {
+        Block {
+            number: slot as u64,
+            hash: B256::repeat_byte(hash_byte),
+            timestamp: slot as u64 * 12,
+            slot,
+            blob_gas_used: U256::ZERO,
+            excess_blob_gas: U256::ZERO,
+            blob_base_fee: None,
+            fork: None,
+        }
+    }
+
+    fn blob(slot: u32) -> Blob {
+        Blob {
+            versioned_hash: B256::repeat_byte(slot as u8),
+            commitment: "0xc0".to_string(),
+            proof: "0xc1".to_string(),
+            data: Some(vec![1u8; 131_072].into()),
+            data_reference: None,
+            tx_hash: B256::ZERO,
+            index: 0,
+            metadata: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn reopens_indexed_blocks_of_records() {
+        let dir = sink_dir("reopen");
+        let sink = FileSink::try_new(&dir).unwrap();
+
+        sink.index(block(10, 1), vec![], vec![blob(10)])
+            .await
+            .unwrap();
+        sink.index_batch(vec![
+            IndexRequest {
+                block: block(11, 2),
+                transactions: vec![],
+                blobs: vec![blob(11)],
+            },
+            IndexRequest {
+                block: block(12, 3),
+                transactions: vec![],
+                blobs: vec![],
+            },
+        ])
+        .await
+        .unwrap();
+        sink.handle_reorg(vec![B256::repeat_byte(3)], vec![])
+            .await
+            .unwrap();
+
+        drop(sink);
+
+        let sink = FileSink::try_open(&dir).unwrap();
+
+        assert_eq!(
+            sink.get_block(11).await.unwrap().map(|block| block.hash),
+            Some(B256::repeat_byte(2))
+        );
+        assert!(sink.get_block(10).await.unwrap().is_some());
+        assert!(sink.get_block(12).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn fails_to_open_invalid_records() {
+        let dir = sink_dir("invalid");
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(RECORDS_FILE_NAME),
+            "{\"type\":\"reorg\",\"forwardedBlocks\":[],\"rewindedBlocks\":[]}\n{\"type\":\"index\"}\n",
+        )
+        .unwrap();
+
+        let error = FileSink::try_open(&dir).unwrap_err();
+
+        assert!(error.to_string().starts_with("Invalid record at line 2"));
+    }
+}
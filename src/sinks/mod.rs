@@ -0,0 +1,78 @@
+use std::{fmt::Debug, time::Duration};
+
+use alloy::primitives::B256;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+use mockall::automock;
+
+use crate::clients::{
+    blobscan::types::{
+        Blob, BlobscanBlock, Block, BlockchainSyncState, FailedSlotsChunk, IndexRequest,
+        Transaction,
+    },
+    common::ClientResult,
+};
+
+pub use self::{dry_run::DryRunSink, file::FileSink, postgres::PostgresSink};
+
+mod blobscan;
+mod dry_run;
+mod file;
+mod postgres;
+
+/// Destination where indexed blocks, transactions and blobs are written to.
+#[async_trait]
+#[cfg_attr(test, automock)]
+pub trait Sink: Send + Sync + Debug {
+    async fn index(
+        &self,
+        block: Block,
+        transactions: Vec<Transaction>,
+        blobs: Vec<Blob>,
+    ) -> ClientResult<()>;
+    /// Indexes several blocks at once, in the given order. Sinks without batch support index
+    /// them one by one.
+    async fn index_batch(&self, requests: Vec<IndexRequest>) -> ClientResult<()> {
+        for request in requests {
+            self.index(request.block, request.transactions, request.blobs)
+                .await?;
+        }
+
+        Ok(())
+    }
+    async fn get_block(&self, slot: u32) -> ClientResult<Option<BlobscanBlock>>;
+    async fn handle_reorg(
+        &self,
+        rewinded_blocks: Vec<B256>,
+        forwarded_blocks: Vec<B256>,
+    ) -> ClientResult<()>;
+    async fn update_sync_state(&self, sync_state: BlockchainSyncState) -> ClientResult<()>;
+    async fn get_sync_state(&self) -> ClientResult<Option<BlockchainSyncState>>;
+    /// Queues slot ranges that failed to be indexed so they can be retried later.
+    async fn add_failed_slots_chunks(&self, chunks: Vec<FailedSlotsChunk>) -> ClientResult<()>;
+    async fn get_failed_slots_chunks(&self) -> ClientResult<Vec<FailedSlotsChunk>>;
+    async fn remove_failed_slots_chunks(&self, chunk_ids: Vec<u32>) -> ClientResult<()>;
+}
+
+/// Limits of the blocks submitted together through [`Sink::index_batch`] while syncing slot
+/// ranges. A batch holds the blocks fetched ahead and is submitted once it reaches the block or
+/// byte limit, or once no further block is fetched within the maximum delay.
+#[derive(Debug, Clone)]
+pub struct IndexBatchConfig {
+    /// Maximum number of blocks submitted in a single batch
+    pub max_blocks: usize,
+    /// Maximum serialized size of a batch, in bytes
+    pub max_bytes: usize,
+    /// Maximum time a batch waits for the next block
+    pub max_delay: Duration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkType {
+    Blobscan,
+    File,
+    Postgres,
+}
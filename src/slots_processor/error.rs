@@ -8,6 +8,14 @@ pub enum SlotProcessingError {
     ClientError(#[from] crate::clients::common::ClientError),
     #[error(transparent)]
     Provider(#[from] alloy::transports::TransportError),
+    #[error("Blob sidecar with versioned hash {versioned_hash} and commitment {commitment} from slot {slot} failed KZG proof verification")]
+    InvalidBlobSidecar {
+        slot: u32,
+        versioned_hash: B256,
+        commitment: String,
+    },
+    #[error("Secondary beacon node disagrees on the {check} of slot {slot}")]
+    BeaconNodesMismatch { slot: u32, check: &'static str },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -1,10 +1,21 @@
 use std::collections::HashMap;
 
-use crate::{clients::beacon::types::Blob as BeaconBlob, utils::web3::calculate_versioned_hash};
+use crate::{
+    clients::beacon::types::{Blob as BeaconBlob, DataColumnSidecar},
+    utils::web3::{calculate_versioned_hash, compute_blob_kzg_proof, verify_blob_kzg_proof},
+};
 use alloy::{
-    primitives::B256,
+    primitives::{Bytes, B256},
     rpc::types::{Block, Transaction},
 };
+use anyhow::{anyhow, Context};
+use c_kzg::KzgSettings;
+
+use super::error::SlotProcessingError;
+
+/// Number of columns holding the original blob cells, as data columns extend blobs to twice
+/// their size while keeping their original cells first.
+const ORIGINAL_DATA_COLUMNS: u32 = 64;
 
 pub fn create_tx_hash_versioned_hashes_mapping(
     block: &Block<Transaction>,
@@ -35,3 +46,279 @@ pub fn create_versioned_hash_blob_mapping(
 
     Ok(version_hash_to_blob)
 }
+
+/// Checks that the blob sidecar's data matches its commitment by verifying its KZG proof.
+pub fn verify_blob_sidecar(
+    slot: u32,
+    versioned_hash: &B256,
+    blob: &BeaconBlob,
+    kzg_settings: &KzgSettings,
+) -> Result<(), SlotProcessingError> {
+    let is_valid_blob = verify_blob_kzg_proof(
+        &blob.blob,
+        &blob.kzg_commitment,
+        &blob.kzg_proof,
+        kzg_settings,
+    )
+    .with_context(|| {
+        format!("Failed to verify sidecar of blob with versioned hash {versioned_hash}")
+    })?;
+
+    if !is_valid_blob {
+        return Err(SlotProcessingError::InvalidBlobSidecar {
+            slot,
+            versioned_hash: *versioned_hash,
+            commitment: blob.kzg_commitment.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Reconstructs the blobs of a block from the cells of the data columns holding the original
+/// blob data, computing their KZG proofs along the way. Requires the beacon node to custody the
+/// first half of the columns, as supernodes do.
+pub fn reconstruct_blobs_from_data_columns(
+    data_columns: &[DataColumnSidecar],
+    kzg_settings: &KzgSettings,
+) -> Result<Vec<BeaconBlob>, anyhow::Error> {
+    let data_columns_by_index = data_columns
+        .iter()
+        .map(|data_column| (data_column.index, data_column))
+        .collect::<HashMap<_, _>>();
+    let original_data_columns = (0..ORIGINAL_DATA_COLUMNS)
+        .map(|index| {
+            data_columns_by_index.get(&index).copied().with_context(|| {
+                format!("Data column {index} is required to reconstruct blobs but is missing")
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let commitments = &original_data_columns[0].kzg_commitments;
+
+    if let Some(data_column) = original_data_columns
+        .iter()
+        .find(|data_column| data_column.column.len() != commitments.len())
+    {
+        return Err(anyhow!(
+            "Data column {} has {} cells but there are {} blob commitments",
+            data_column.index,
+            data_column.column.len(),
+            commitments.len()
+        ));
+    }
+
+    commitments
+        .iter()
+        .enumerate()
+        .map(|(i, commitment)| {
+            let blob = original_data_columns
+                .iter()
+                .flat_map(|data_column| data_column.column[i].iter().copied())
+                .collect::<Bytes>();
+            let kzg_proof = compute_blob_kzg_proof(&blob, commitment, kzg_settings)?;
+
+            Ok(BeaconBlob {
+                kzg_commitment: commitment.clone(),
+                kzg_proof,
+                blob,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use c_kzg::{ethereum_kzg_settings, Blob as KzgBlob, KzgCommitment};
+
+    use super::*;
+
+    const BYTES_PER_BLOB: usize = 131_072;
+    const TOTAL_DATA_COLUMNS: u32 = 2 * ORIGINAL_DATA_COLUMNS;
+
+    /// Blob whose field elements start with a zero byte, so they're all below the modulus.
+    fn blob(seed: u8) -> Vec<u8> {
+        (0..BYTES_PER_BLOB)
+            .map(|i| {
+                if i % 32 == 0 {
+                    0
+                } else {
+                    (i as u8).wrapping_mul(seed)
+                }
+            })
+            .collect()
+    }
+
+    fn commitment(blob: &[u8]) -> String {
+        let kzg_blob = KzgBlob::from_bytes(blob).unwrap();
+        let commitment =
+            KzgCommitment::blob_to_kzg_commitment(&kzg_blob, ethereum_kzg_settings()).unwrap();
+
+        format!("0x{}", commitment.as_hex_string())
+    }
+
+    /// Splits the blobs into the cells of the original data columns. The extension columns are
+    /// filled with arbitrary cells, as they aren't used to reconstruct blobs.
+    fn data_columns(blobs: &[Vec<u8>]) -> Vec<DataColumnSidecar> {
+        let commitments = blobs
+            .iter()
+            .map(|blob| commitment(blob))
+            .collect::<Vec<_>>();
+        let cell_size = BYTES_PER_BLOB / ORIGINAL_DATA_COLUMNS as usize;
+
+        (0..TOTAL_DATA_COLUMNS)
+            .map(|index| DataColumnSidecar {
+                index,
+                column: blobs
+                    .iter()
+                    .map(|blob| {
+                        let start = (index % ORIGINAL_DATA_COLUMNS) as usize * cell_size;
+
+                        Bytes::copy_from_slice(&blob[start..start + cell_size])
+                    })
+                    .collect(),
+                kzg_commitments: commitments.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reconstructs_blobs_from_data_columns() {
+        let blobs = vec![blob(3), blob(7)];
+        let mut data_columns = data_columns(&blobs);
+
+        // Columns may come in any order
+        data_columns.reverse();
+
+        let reconstructed_blobs =
+            reconstruct_blobs_from_data_columns(&data_columns, ethereum_kzg_settings()).unwrap();
+
+        assert_eq!(reconstructed_blobs.len(), 2);
+
+        for (reconstructed_blob, blob) in reconstructed_blobs.iter().zip(&blobs) {
+            assert_eq!(reconstructed_blob.blob.as_ref(), blob.as_slice());
+            assert_eq!(reconstructed_blob.kzg_commitment, commitment(blob));
+            assert!(verify_blob_kzg_proof(
+                &reconstructed_blob.blob,
+                &reconstructed_blob.kzg_commitment,
+                &reconstructed_blob.kzg_proof,
+                ethereum_kzg_settings(),
+            )
+            .unwrap());
+        }
+    }
+
+    #[test]
+    fn reconstructs_blobs_from_original_data_columns_only() {
+        let blobs = vec![blob(5)];
+        let data_columns = data_columns(&blobs)
+            .into_iter()
+            .filter(|data_column| data_column.index < ORIGINAL_DATA_COLUMNS)
+            .collect::<Vec<_>>();
+
+        let reconstructed_blobs =
+            reconstruct_blobs_from_data_columns(&data_columns, ethereum_kzg_settings()).unwrap();
+
+        assert_eq!(reconstructed_blobs[0].blob.as_ref(), blobs[0].as_slice());
+    }
+
+    #[test]
+    fn fails_when_original_data_columns_are_missing() {
+        let data_columns = data_columns(&[blob(3)])
+            .into_iter()
+            .filter(|data_column| data_column.index != 10)
+            .collect::<Vec<_>>();
+
+        let error = reconstruct_blobs_from_data_columns(&data_columns, ethereum_kzg_settings())
+            .unwrap_err();
+
+        assert!(error.to_string().contains("Data column 10"));
+    }
+
+    #[test]
+    fn fails_when_data_columns_have_missing_cells() {
+        let mut data_columns = data_columns(&[blob(3), blob(7)]);
+
+        data_columns[20].column.pop();
+
+        assert!(
+            reconstruct_blobs_from_data_columns(&data_columns, ethereum_kzg_settings()).is_err()
+        );
+    }
+
+    #[test]
+    fn returns_no_blobs_for_empty_data_columns() {
+        let data_columns = data_columns(&[]);
+
+        let reconstructed_blobs =
+            reconstruct_blobs_from_data_columns(&data_columns, ethereum_kzg_settings()).unwrap();
+
+        assert!(reconstructed_blobs.is_empty());
+    }
+
+    fn blob_sidecar(seed: u8) -> BeaconBlob {
+        let blob = blob(seed);
+        let kzg_commitment = commitment(&blob);
+        let kzg_proof =
+            compute_blob_kzg_proof(&blob, &kzg_commitment, ethereum_kzg_settings()).unwrap();
+
+        BeaconBlob {
+            kzg_commitment,
+            kzg_proof,
+            blob: blob.into(),
+        }
+    }
+
+    fn assert_invalid_blob_sidecar(blob: &BeaconBlob) {
+        let versioned_hash = B256::repeat_byte(1);
+
+        match verify_blob_sidecar(10, &versioned_hash, blob, ethereum_kzg_settings()) {
+            Err(SlotProcessingError::InvalidBlobSidecar {
+                slot,
+                versioned_hash: invalid_versioned_hash,
+                commitment,
+            }) => {
+                assert_eq!(slot, 10);
+                assert_eq!(invalid_versioned_hash, versioned_hash);
+                assert_eq!(commitment, blob.kzg_commitment);
+            }
+            result => panic!("Expected an invalid blob sidecar error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_blob_sidecar() {
+        let blob = blob_sidecar(3);
+
+        assert!(verify_blob_sidecar(10, &B256::ZERO, &blob, ethereum_kzg_settings()).is_ok());
+    }
+
+    #[test]
+    fn rejects_blob_sidecar_with_tampered_blob() {
+        let mut blob = blob_sidecar(3);
+        let mut data = blob.blob.to_vec();
+
+        // Keeps the field element below the modulus by leaving its first byte untouched
+        data[1] ^= 1;
+        blob.blob = data.into();
+
+        assert_invalid_blob_sidecar(&blob);
+    }
+
+    #[test]
+    fn rejects_blob_sidecar_with_tampered_commitment() {
+        let mut blob = blob_sidecar(3);
+
+        blob.kzg_commitment = blob_sidecar(7).kzg_commitment;
+
+        assert_invalid_blob_sidecar(&blob);
+    }
+
+    #[test]
+    fn rejects_blob_sidecar_with_tampered_proof() {
+        let mut blob = blob_sidecar(3);
+
+        blob.kzg_proof = blob_sidecar(7).kzg_proof;
+
+        assert_invalid_blob_sidecar(&blob);
+    }
+}
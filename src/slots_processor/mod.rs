@@ -1,26 +1,43 @@
-use alloy::{
-    primitives::B256, rpc::types::BlockTransactionsKind, transports::http::ReqwestTransport,
-};
+use alloy::{primitives::B256, rpc::types::BlockTransactionsKind, transports::Transport};
 use anyhow::{anyhow, Context as AnyhowContext, Result};
 
-use crate::clients::beacon::types::BlockHeader;
-use tracing::{debug, info, Instrument};
+use crate::clients::beacon::types::{Blob as BeaconBlob, BlockHeader};
+use futures::{stream, Future, StreamExt};
+use std::{mem, sync::Arc, time::Duration};
+use tokio::{sync::mpsc, task::JoinHandle, time::timeout};
+use tracing::{debug, info, warn, Instrument};
 
 use crate::{
+    blob_analysis::analyze_blob,
     clients::{
-        blobscan::types::{Blob, BlobscanBlock, Block, Transaction},
+        blobscan::types::{Blob, BlobscanBlock, Block, IndexRequest, Transaction},
         common::ClientError,
+        execution::get_transaction_receipts,
     },
     context::CommonContext,
+    metrics,
+    network::Fork,
+    rollups::TransactionCategory,
+    sinks::IndexBatchConfig,
+    utils::web3::calculate_blob_base_fee,
 };
 
 use self::error::{SlotProcessingError, SlotsProcessorError};
-use self::helpers::{create_tx_hash_versioned_hashes_mapping, create_versioned_hash_blob_mapping};
+use self::helpers::{
+    create_tx_hash_versioned_hashes_mapping, create_versioned_hash_blob_mapping,
+    reconstruct_blobs_from_data_columns, verify_blob_sidecar,
+};
 
 pub mod error;
 mod helpers;
 
 const MAX_ALLOWED_REORG_DEPTH: u32 = 100;
+const MAX_CROSS_CHECK_ATTEMPTS: u32 = 3;
+/// Kept short and fixed as slots are cross-checked while being fetched ahead, where a slot
+/// waiting on the secondary node holds back the submission of the ones after it.
+const CROSS_CHECK_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Amount of slots fetched ahead of the one being submitted.
+const PIPELINE_DEPTH: usize = 4;
 
 pub struct BlockData {
     pub root: B256,
@@ -39,16 +56,98 @@ impl From<&BlockData> for BlockHeader {
     }
 }
 
+/// Slot data fetched ahead of its submission.
+struct FetchedSlot {
+    block_header: Option<BlockHeader>,
+    index_request: Option<IndexRequest>,
+}
+
+/// Blocks waiting to be submitted together.
+#[derive(Default)]
+struct PendingBlocks {
+    block_headers: Vec<BlockHeader>,
+    index_requests: Vec<IndexRequest>,
+    bytes: usize,
+}
+
+impl PendingBlocks {
+    fn is_empty(&self) -> bool {
+        self.index_requests.is_empty()
+    }
+
+    fn push(&mut self, block_header: BlockHeader, index_request: IndexRequest, bytes: usize) {
+        self.block_headers.push(block_header);
+        self.index_requests.push(index_request);
+        self.bytes += bytes;
+    }
+
+    /// Returns whether a block of the given size can be added without exceeding the batch size.
+    /// Blocks are submitted one by one when batches are disabled.
+    fn fits(&self, bytes: usize, config: Option<&IndexBatchConfig>) -> bool {
+        match config {
+            Some(config) => self.is_empty() || self.bytes + bytes <= config.max_bytes,
+            None => self.is_empty(),
+        }
+    }
+
+    fn is_full(&self, config: Option<&IndexBatchConfig>) -> bool {
+        match config {
+            Some(config) => {
+                self.index_requests.len() >= config.max_blocks || self.bytes >= config.max_bytes
+            }
+            None => !self.is_empty(),
+        }
+    }
+}
+
 pub struct SlotsProcessor<T> {
     context: Box<dyn CommonContext<T>>,
     pub last_processed_block: Option<BlockHeader>,
 }
 
-impl SlotsProcessor<ReqwestTransport> {
+/// Fetches the given slots in a new task, up to `PIPELINE_DEPTH` at a time, and sends them in
+/// order. Fetching stops once the receiver is dropped.
+fn spawn_slots_fetching<F, Fut, R>(
+    slots: Vec<u32>,
+    fetch: F,
+) -> (mpsc::Receiver<(u32, R)>, JoinHandle<()>)
+where
+    F: Fn(u32) -> Fut + Send + 'static,
+    Fut: Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(PIPELINE_DEPTH);
+    let fetch_task = tokio::spawn(
+        async move {
+            let mut fetched_slots = stream::iter(slots)
+                .map(move |slot| {
+                    let fetched_slot = fetch(slot);
+
+                    async move { (slot, fetched_slot.await) }
+                })
+                .buffered(PIPELINE_DEPTH);
+
+            while let Some(fetched_slot) = fetched_slots.next().await {
+                // The receiver is gone once submitting a slot fails
+                if tx.send(fetched_slot).await.is_err() {
+                    break;
+                }
+            }
+        }
+        .in_current_span(),
+    );
+
+    (rx, fetch_task)
+}
+
+impl<T> SlotsProcessor<T>
+where
+    T: Transport + Clone + Send + Sync + 'static,
+{
     pub fn new(
-        context: Box<dyn CommonContext<ReqwestTransport>>,
+        context: Box<dyn CommonContext<T>>,
         last_processed_block: Option<BlockHeader>,
-    ) -> SlotsProcessor<ReqwestTransport> {
+    ) -> SlotsProcessor<T> {
         Self {
             context,
             last_processed_block,
@@ -67,15 +166,85 @@ impl SlotsProcessor<ReqwestTransport> {
             (initial_slot..final_slot).collect::<Vec<_>>()
         };
 
+        // Upcoming slots are fetched in a separate task so that their beacon and execution data
+        // is retrieved while the current one is being submitted
+        let fetcher = Arc::new(SlotsProcessor::new(self.context.clone(), None));
+        let (rx, fetch_task) = spawn_slots_fetching(slots, move |slot| {
+            let fetcher = fetcher.clone();
+
+            async move { fetcher.fetch_slot(slot).await }
+        });
+
+        let result = self.submit_slots(initial_slot, final_slot, rx).await;
+
+        if result.is_err() {
+            fetch_task.abort();
+
+            return result;
+        }
+
+        fetch_task
+            .await
+            .map_err(|err| anyhow!("Slots fetching task panicked: {:?}", err))?;
+
+        result
+    }
+
+    /// Submits the fetched slots in order, handling the reorgs found along the way. With index
+    /// batches enabled, the blocks fetched ahead are submitted together, one batch after another.
+    async fn submit_slots(
+        &mut self,
+        initial_slot: u32,
+        final_slot: u32,
+        mut fetched_slots: mpsc::Receiver<(u32, Result<FetchedSlot, SlotProcessingError>)>,
+    ) -> Result<(), SlotsProcessorError> {
+        let is_reverse = initial_slot > final_slot;
         let mut last_processed_block = self.last_processed_block.clone();
+        let mut pending_blocks = PendingBlocks::default();
+        let index_batch_config = self.context.index_batch_config().cloned();
+        let to_processing_error =
+            |(failed_slot, error)| SlotsProcessorError::FailedSlotsProcessing {
+                initial_slot,
+                final_slot,
+                failed_slot,
+                error,
+            };
 
-        for current_slot in slots {
-            let block_header = match self
-                .context
-                .beacon_client()
-                .get_block_header(current_slot.into())
-                .await?
-            {
+        loop {
+            let next_fetched_slot = match &index_batch_config {
+                Some(config) if !pending_blocks.is_empty() => {
+                    match timeout(config.max_delay, fetched_slots.recv()).await {
+                        Ok(next_fetched_slot) => next_fetched_slot,
+                        Err(_) => {
+                            self.submit_pending_blocks(&mut pending_blocks)
+                                .await
+                                .map_err(to_processing_error)?;
+
+                            continue;
+                        }
+                    }
+                }
+                _ => fetched_slots.recv().await,
+            };
+            let Some((current_slot, fetched_slot)) = next_fetched_slot else {
+                break;
+            };
+            let fetched_slot = match fetched_slot {
+                Ok(fetched_slot) => fetched_slot,
+                Err(error) => {
+                    // The blocks before the failed slot are still indexed
+                    self.submit_pending_blocks(&mut pending_blocks)
+                        .await
+                        .map_err(to_processing_error)?;
+
+                    return Err(to_processing_error((current_slot, error)));
+                }
+            };
+            let FetchedSlot {
+                block_header,
+                index_request,
+            } = fetched_slot;
+            let block_header = match block_header {
                 Some(header) => header,
                 None => {
                     debug!(current_slot, "Skipping as there is no beacon block header");
@@ -97,6 +266,11 @@ impl SlotsProcessor<ReqwestTransport> {
                             "Reorg detected!",
                         );
 
+                        // The reorg is resolved against the sink, so it must hold every block
+                        // processed so far
+                        self.submit_pending_blocks(&mut pending_blocks)
+                            .await
+                            .map_err(to_processing_error)?;
                         self.process_reorg(&prev_block_header, &block_header)
                             .await
                             .map_err(|error| SlotsProcessorError::FailedReorgProcessing {
@@ -110,38 +284,215 @@ impl SlotsProcessor<ReqwestTransport> {
                 }
             }
 
-            if let Err(error) = self.process_block(&block_header).await {
-                return Err(SlotsProcessorError::FailedSlotsProcessing {
-                    initial_slot,
-                    final_slot,
-                    failed_slot: current_slot,
-                    error,
-                });
+            if let Some(index_request) = index_request {
+                let index_request = self
+                    .store_blobs(index_request)
+                    .await
+                    .map_err(|error| to_processing_error((current_slot, error)))?;
+                let request_bytes = match &index_batch_config {
+                    Some(_) => serde_json::to_vec(&index_request)
+                        .map_err(|error| {
+                            to_processing_error((current_slot, anyhow!(error).into()))
+                        })?
+                        .len(),
+                    None => 0,
+                };
+
+                // Submit the pending blocks first if the block doesn't fit along with them
+                if !pending_blocks.fits(request_bytes, index_batch_config.as_ref()) {
+                    self.submit_pending_blocks(&mut pending_blocks)
+                        .await
+                        .map_err(to_processing_error)?;
+                }
+
+                pending_blocks.push(block_header.clone(), index_request, request_bytes);
+
+                if pending_blocks.is_full(index_batch_config.as_ref()) {
+                    self.submit_pending_blocks(&mut pending_blocks)
+                        .await
+                        .map_err(to_processing_error)?;
+                }
             }
 
             last_processed_block = Some(block_header);
         }
 
+        self.submit_pending_blocks(&mut pending_blocks)
+            .await
+            .map_err(to_processing_error)?;
+
         self.last_processed_block = last_processed_block;
 
         Ok(())
     }
 
-    async fn process_block(
+    /// Fetches the block header of a slot along with the entities to be indexed, which are left
+    /// out if the slot has no blobs or the journal, if any, records the block as already indexed.
+    async fn fetch_slot(&self, slot: u32) -> Result<FetchedSlot, SlotProcessingError> {
+        let block_header = match self
+            .context
+            .beacon_client()
+            .get_block_header(slot.into())
+            .await?
+        {
+            Some(header) => header,
+            None => {
+                return Ok(FetchedSlot {
+                    block_header: None,
+                    index_request: None,
+                })
+            }
+        };
+
+        self.cross_check_block_header(&block_header).await?;
+
+        if let Some(journal) = self.context.journal() {
+            if journal.is_block_indexed(&block_header).await? {
+                debug!(slot, "Skipping as block was already indexed");
+
+                return Ok(FetchedSlot {
+                    block_header: Some(block_header),
+                    index_request: None,
+                });
+            }
+        }
+
+        let index_request = self.build_index_request(slot).await?;
+
+        Ok(FetchedSlot {
+            block_header: Some(block_header),
+            index_request,
+        })
+    }
+
+    /// Submits the pending blocks at once, recording them in the journal, if any, once indexed.
+    /// On failure, returns the first slot of the blocks along with the error.
+    async fn submit_pending_blocks(
+        &self,
+        pending_blocks: &mut PendingBlocks,
+    ) -> Result<(), (u32, SlotProcessingError)> {
+        let PendingBlocks {
+            block_headers,
+            index_requests,
+            ..
+        } = mem::take(pending_blocks);
+        let first_slot = match block_headers.first() {
+            Some(block_header) => block_header.slot,
+            None => return Ok(()),
+        };
+
+        self.submit_index_requests(index_requests)
+            .await
+            .map_err(|error| (first_slot, error))?;
+
+        if let Some(journal) = self.context.journal() {
+            for block_header in &block_headers {
+                journal
+                    .record_indexed_block(block_header)
+                    .await
+                    .map_err(|error| (block_header.slot, error.into()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Indexes a single block, recording it in the journal, if any, as the slots submitted in
+    /// order are.
+    pub async fn process_block(
         &self,
         beacon_block_header: &BlockHeader,
     ) -> Result<(), SlotProcessingError> {
+        let slot = beacon_block_header.slot;
+
+        let index_request = match self.build_index_request(slot).await? {
+            Some(index_request) => self.store_blobs(index_request).await?,
+            None => return Ok(()),
+        };
+
+        self.submit_index_requests(vec![index_request]).await?;
+
+        if let Some(journal) = self.context.journal() {
+            journal.record_indexed_block(beacon_block_header).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the blob data to the blob storage, if any, leaving a reference to it in its place.
+    async fn store_blobs(
+        &self,
+        mut index_request: IndexRequest,
+    ) -> Result<IndexRequest, SlotProcessingError> {
+        if let Some(blob_storage) = self.context.blob_storage() {
+            for blob in index_request.blobs.iter_mut() {
+                if let Some(data) = blob.data.take() {
+                    blob.data_reference =
+                        Some(blob_storage.store(&blob.versioned_hash, &data).await?);
+                }
+            }
+        }
+
+        Ok(index_request)
+    }
+
+    /// Indexes the given blocks, in a single batch when there are several of them.
+    async fn submit_index_requests(
+        &self,
+        index_requests: Vec<IndexRequest>,
+    ) -> Result<(), SlotProcessingError> {
+        let indexed_blocks = index_requests
+            .iter()
+            .map(|index_request| {
+                (
+                    index_request.block.slot,
+                    index_request.block.number,
+                    index_request.transactions.len() as u64,
+                    index_request.blobs.len() as u64,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let result = if index_requests.len() == 1 {
+            let IndexRequest {
+                block,
+                transactions,
+                blobs,
+            } = index_requests.into_iter().next().unwrap();
+
+            self.context.sink().index(block, transactions, blobs).await
+        } else {
+            self.context.sink().index_batch(index_requests).await
+        };
+
+        result.map_err(SlotProcessingError::ClientError)?;
+
+        for (slot, block_number, total_transactions, total_blobs) in indexed_blocks {
+            metrics::INDEXED_BLOCKS.inc();
+            metrics::INDEXED_TRANSACTIONS.inc_by(total_transactions);
+            metrics::INDEXED_BLOBS.inc_by(total_blobs);
+
+            info!(slot, block_number, "Block indexed successfully");
+        }
+
+        Ok(())
+    }
+
+    /// Fetches the beacon and execution data of the given slot and creates the entities to be
+    /// indexed, without writing anything. Returns `None` if the slot has no blobs.
+    pub async fn build_index_request(
+        &self,
+        slot: u32,
+    ) -> Result<Option<IndexRequest>, SlotProcessingError> {
         let beacon_client = self.context.beacon_client();
-        let blobscan_client = self.context.blobscan_client();
         let provider = self.context.provider();
-        let slot = beacon_block_header.slot;
 
         let beacon_block = match beacon_client.get_block(slot.into()).await? {
             Some(block) => block,
             None => {
                 debug!(slot = slot, "Skipping as there is no beacon block");
 
-                return Ok(());
+                return Ok(None);
             }
         };
 
@@ -153,32 +504,48 @@ impl SlotsProcessor<ReqwestTransport> {
                     "Skipping as beacon block doesn't contain execution payload"
                 );
 
-                return Ok(());
+                return Ok(None);
             }
         };
 
-        let has_kzg_blob_commitments = match beacon_block.blob_kzg_commitments {
-            Some(commitments) => !commitments.is_empty(),
-            None => false,
-        };
+        let blob_kzg_commitments_count = beacon_block
+            .blob_kzg_commitments
+            .as_ref()
+            .map_or(0, |commitments| commitments.len());
 
-        if !has_kzg_blob_commitments {
+        if blob_kzg_commitments_count == 0 {
             debug!(
                 slot,
                 "Skipping as beacon block doesn't contain blob kzg commitments"
             );
 
-            return Ok(());
+            return Ok(None);
+        }
+
+        let blob_parameters = self.context.blob_schedule().parameters_at(slot);
+
+        if blob_kzg_commitments_count > blob_parameters.max_blobs_per_block as usize {
+            warn!(
+                slot,
+                blob_kzg_commitments_count,
+                max_blobs_per_block = blob_parameters.max_blobs_per_block,
+                "Beacon block contains more blobs than allowed by the blob schedule"
+            );
         }
 
         let execution_block_hash = execution_payload.block_hash;
 
         // Fetch execution block and perform some checks
 
-        let execution_block = provider
-            .get_block(execution_block_hash.into(), BlockTransactionsKind::Full)
-            .await?
-            .with_context(|| format!("Execution block {execution_block_hash} not found"))?;
+        let execution_block = match self.context.execution_quorum() {
+            Some(execution_quorum) => execution_quorum.get_block(execution_block_hash).await?,
+            None => {
+                provider
+                    .get_block(execution_block_hash.into(), BlockTransactionsKind::Full)
+                    .await?
+            }
+        }
+        .with_context(|| format!("Execution block {execution_block_hash} not found"))?;
 
         let tx_hash_to_versioned_hashes =
             create_tx_hash_versioned_hashes_mapping(&execution_block)?;
@@ -189,16 +556,28 @@ impl SlotsProcessor<ReqwestTransport> {
 
         // Fetch blobs and perform some checks
 
-        let blobs = match beacon_client
-            .get_blobs(slot.into())
-            .await
-            .map_err(SlotProcessingError::ClientError)?
-        {
+        let fork = self.context.fork_schedule().fork_at(slot);
+        let blobs = match beacon_client.get_blobs(slot.into()).await {
+            Ok(Some(blobs)) => Some(blobs),
+            // Blob sidecars may no longer be served once data columns are used
+            Ok(None) if fork >= Fork::Fulu => self.get_blobs_from_data_columns(slot).await?,
+            Err(error) if fork >= Fork::Fulu => {
+                warn!(
+                    slot,
+                    ?error,
+                    "Failed to fetch blob sidecars. Reconstructing blobs from data column sidecars"
+                );
+
+                self.get_blobs_from_data_columns(slot).await?
+            }
+            result => result.map_err(SlotProcessingError::ClientError)?,
+        };
+        let blobs = match blobs {
             Some(blobs) => {
                 if blobs.is_empty() {
                     debug!(slot, "Skipping as blobs sidecar is empty");
 
-                    return Ok(());
+                    return Ok(None);
                 } else {
                     blobs
                 }
@@ -206,35 +585,95 @@ impl SlotsProcessor<ReqwestTransport> {
             None => {
                 debug!(slot, "Skipping as there is no blobs sidecar");
 
-                return Ok(());
+                return Ok(None);
             }
         };
 
+        self.cross_check_blob_commitments(slot, &blobs).await?;
+
         // Create entities to be indexed
 
-        let block_entity = Block::try_from((&execution_block, slot))?;
+        let mut block_entity = Block::try_from((&execution_block, slot))?;
+
+        block_entity.fork = Some(fork);
+        block_entity.blob_base_fee = Some(calculate_blob_base_fee(
+            block_entity.excess_blob_gas,
+            blob_parameters.min_base_fee_per_blob_gas,
+            blob_parameters.base_fee_update_fraction,
+        ));
+
         let block_transactions = execution_block
             .transactions
             .as_transactions()
             .ok_or_else(|| anyhow!("Failed to parse transactions"))?;
 
+        let blob_tx_hashes = tx_hash_to_versioned_hashes
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        let tx_hash_to_receipt = match self.context.execution_quorum() {
+            Some(execution_quorum) => {
+                execution_quorum
+                    .get_transaction_receipts(execution_block_hash, &blob_tx_hashes)
+                    .await?
+            }
+            None => {
+                get_transaction_receipts(provider, execution_block_hash, &blob_tx_hashes).await?
+            }
+        };
+
+        let rollup_registry = self.context.rollup_registry();
         let transactions_entities = block_transactions
             .iter()
             .filter(|tx| tx_hash_to_versioned_hashes.contains_key(&tx.hash))
-            .map(|tx| Transaction::try_from((tx, &execution_block)))
+            .map(|tx| {
+                let receipt = tx_hash_to_receipt
+                    .get(&tx.hash)
+                    .with_context(|| format!("Receipt not found for tx {}", tx.hash))?;
+                let mut transaction = Transaction::try_from((tx, &execution_block, receipt))?;
+
+                transaction.rollup = rollup_registry.get_rollup(&transaction.from);
+                transaction.category = Some(if transaction.rollup.is_some() {
+                    TransactionCategory::Rollup
+                } else {
+                    TransactionCategory::Other
+                });
+
+                Ok(transaction)
+            })
             .collect::<Result<Vec<Transaction>>>()?;
 
         let versioned_hash_to_blob = create_versioned_hash_blob_mapping(&blobs)?;
         let mut blob_entities: Vec<Blob> = vec![];
+        let mut blob_sidecars = vec![];
 
         for (tx_hash, versioned_hashes) in tx_hash_to_versioned_hashes.iter() {
             for (i, versioned_hash) in versioned_hashes.iter().enumerate() {
                 let blob = *versioned_hash_to_blob.get(versioned_hash).with_context(|| format!("Sidecar not found for blob {i} with versioned hash {versioned_hash} from tx {tx_hash}"))?;
 
-                blob_entities.push(Blob::from((blob, versioned_hash, i, tx_hash)));
+                blob_sidecars.push((*versioned_hash, blob.clone()));
+
+                let mut blob_entity = Blob::from((blob, versioned_hash, i, tx_hash));
+
+                if self.context.blob_analysis_enabled() {
+                    blob_entity.metadata = Some(analyze_blob(&blob.blob));
+                }
+
+                blob_entities.push(blob_entity);
             }
         }
 
+        // KZG proof verification is CPU-bound, so it's kept off the async workers
+        let kzg_settings = self.context.kzg_settings().clone();
+
+        tokio::task::spawn_blocking(move || {
+            blob_sidecars.iter().try_for_each(|(versioned_hash, blob)| {
+                verify_blob_sidecar(slot, versioned_hash, blob, &kzg_settings)
+            })
+        })
+        .await
+        .map_err(anyhow::Error::from)??;
+
         /*
         let tx_hashes = transactions_entities
             .iter()
@@ -246,16 +685,143 @@ impl SlotsProcessor<ReqwestTransport> {
             .collect::<Vec<String>>();
          */
 
-        let block_number = block_entity.number;
+        Ok(Some(IndexRequest {
+            block: block_entity,
+            transactions: transactions_entities,
+            blobs: blob_entities,
+        }))
+    }
 
-        blobscan_client
-            .index(block_entity, transactions_entities, blob_entities)
-            .await
-            .map_err(SlotProcessingError::ClientError)?;
+    async fn get_blobs_from_data_columns(
+        &self,
+        slot: u32,
+    ) -> Result<Option<Vec<BeaconBlob>>, SlotProcessingError> {
+        let data_columns = match self
+            .context
+            .beacon_client()
+            .get_data_column_sidecars(slot.into())
+            .await?
+        {
+            Some(data_columns) => data_columns,
+            None => return Ok(None),
+        };
+        let kzg_settings = self.context.kzg_settings().clone();
+        let blobs = tokio::task::spawn_blocking(move || {
+            reconstruct_blobs_from_data_columns(&data_columns, &kzg_settings)
+        })
+        .await
+        .map_err(anyhow::Error::from)?
+        .with_context(|| format!("Failed to reconstruct blobs of slot {slot}"))?;
+
+        Ok(Some(blobs))
+    }
 
-        info!(slot, block_number, "Block indexed successfully");
+    /// Checks that the secondary beacon node, if any, agrees on the block header. The check is
+    /// retried a few times to give a lagging node time to catch up before giving up on the slot.
+    async fn cross_check_block_header(
+        &self,
+        block_header: &BlockHeader,
+    ) -> Result<(), SlotProcessingError> {
+        let secondary_beacon_client = match self.context.secondary_beacon_client() {
+            Some(beacon_client) => beacon_client,
+            None => return Ok(()),
+        };
+        let slot = block_header.slot;
+
+        for attempt in 1..=MAX_CROSS_CHECK_ATTEMPTS {
+            let matches = match secondary_beacon_client.get_block_header(slot.into()).await {
+                Ok(secondary_block_header) => {
+                    secondary_block_header.is_some_and(|secondary_block_header| {
+                        secondary_block_header.root == block_header.root
+                            && secondary_block_header.parent_root == block_header.parent_root
+                    })
+                }
+                Err(error) => {
+                    warn!(
+                        slot,
+                        ?error,
+                        "Failed to fetch block header from secondary beacon node"
+                    );
+
+                    false
+                }
+            };
 
-        Ok(())
+            if matches {
+                return Ok(());
+            }
+
+            Self::on_cross_check_mismatch(slot, "header", attempt).await;
+        }
+
+        Err(SlotProcessingError::BeaconNodesMismatch {
+            slot,
+            check: "header",
+        })
+    }
+
+    /// Checks that the secondary beacon node, if any, agrees on the blob commitments of the block.
+    /// The commitments are taken from the block, as blob sidecars may no longer be served once
+    /// data columns are used.
+    async fn cross_check_blob_commitments(
+        &self,
+        slot: u32,
+        blobs: &[BeaconBlob],
+    ) -> Result<(), SlotProcessingError> {
+        let secondary_beacon_client = match self.context.secondary_beacon_client() {
+            Some(beacon_client) => beacon_client,
+            None => return Ok(()),
+        };
+
+        for attempt in 1..=MAX_CROSS_CHECK_ATTEMPTS {
+            let matches = match secondary_beacon_client.get_block(slot.into()).await {
+                Ok(secondary_block) => secondary_block
+                    .and_then(|secondary_block| secondary_block.blob_kzg_commitments)
+                    .is_some_and(|secondary_commitments| {
+                        secondary_commitments.len() == blobs.len()
+                            && secondary_commitments.iter().zip(blobs).all(
+                                |(secondary_commitment, blob)| {
+                                    secondary_commitment.eq_ignore_ascii_case(&blob.kzg_commitment)
+                                },
+                            )
+                    }),
+                Err(error) => {
+                    warn!(
+                        slot,
+                        ?error,
+                        "Failed to fetch block from secondary beacon node"
+                    );
+
+                    false
+                }
+            };
+
+            if matches {
+                return Ok(());
+            }
+
+            Self::on_cross_check_mismatch(slot, "blob_commitments", attempt).await;
+        }
+
+        Err(SlotProcessingError::BeaconNodesMismatch {
+            slot,
+            check: "blob_commitments",
+        })
+    }
+
+    async fn on_cross_check_mismatch(slot: u32, check: &'static str, attempt: u32) {
+        metrics::BEACON_NODES_MISMATCHES
+            .with_label_values(&[check])
+            .inc();
+
+        warn!(
+            slot,
+            check, attempt, "Secondary beacon node disagrees with the primary one"
+        );
+
+        if attempt < MAX_CROSS_CHECK_ATTEMPTS {
+            tokio::time::sleep(CROSS_CHECK_RETRY_DELAY).await;
+        }
     }
 
     /// Handles reorgs by rewinding the blobscan blocks to the common ancestor and forwarding to the new head.
@@ -272,11 +838,8 @@ impl SlotsProcessor<ReqwestTransport> {
         while reorg_depth <= MAX_ALLOWED_REORG_DEPTH && current_old_slot > 0 {
             // We iterate over blocks by slot and not block root as blobscan blocks don't
             // have parent root we can use to traverse the chain
-            if let Some(old_blobscan_block) = self
-                .context
-                .blobscan_client()
-                .get_block(current_old_slot)
-                .await?
+            if let Some(old_blobscan_block) =
+                self.context.sink().get_block(current_old_slot).await?
             {
                 let canonical_block_path = self
                     .get_canonical_block_path(&old_blobscan_block, new_head_header.root)
@@ -293,10 +856,13 @@ impl SlotsProcessor<ReqwestTransport> {
                         .collect::<Vec<_>>();
 
                     self.context
-                        .blobscan_client()
+                        .sink()
                         .handle_reorg(rewinded_blocks.clone(), forwarded_blocks.clone())
                         .await?;
 
+                    metrics::REORGS.inc();
+                    metrics::REORG_DEPTH.observe(rewinded_blocks.len() as f64);
+
                     info!(rewinded_blocks = ?rewinded_blocks, forwarded_blocks = ?forwarded_blocks, "Reorg handled!");
 
                     let canonical_block_headers: Vec<BlockHeader> = canonical_block_path
@@ -316,7 +882,7 @@ impl SlotsProcessor<ReqwestTransport> {
                             self.process_block(block)
                                 .instrument(reorg_span)
                                 .await
-                                .with_context(|| format!("Failed to sync forwarded block"))?;
+                                .with_context(|| "Failed to sync forwarded block".to_string())?;
                         }
                     }
 
@@ -393,3 +959,296 @@ impl SlotsProcessor<ReqwestTransport> {
         Ok(vec![])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex as StdMutex,
+    };
+
+    use alloy::{primitives::U256, transports::BoxTransport};
+
+    use crate::{context::SinkContext, sinks::MockSink};
+
+    use super::*;
+
+    fn block_header(slot: u32) -> BlockHeader {
+        BlockHeader {
+            root: B256::repeat_byte(slot as u8 + 1),
+            parent_root: B256::repeat_byte(slot as u8),
+            slot,
+        }
+    }
+
+    fn index_request(slot: u32) -> IndexRequest {
+        IndexRequest {
+            block: Block {
+                number: slot as u64,
+                hash: B256::repeat_byte(slot as u8),
+                timestamp: slot as u64 * 12,
+                slot,
+                blob_gas_used: U256::ZERO,
+                excess_blob_gas: U256::ZERO,
+                blob_base_fee: None,
+                fork: None,
+            },
+            transactions: vec![],
+            blobs: vec![],
+        }
+    }
+
+    fn fetched_slot(slot: u32) -> (u32, Result<FetchedSlot, SlotProcessingError>) {
+        (
+            slot,
+            Ok(FetchedSlot {
+                block_header: Some(block_header(slot)),
+                index_request: Some(index_request(slot)),
+            }),
+        )
+    }
+
+    /// Slots of every submission made to the sink, in order.
+    type Submissions = Arc<StdMutex<Vec<Vec<u32>>>>;
+
+    /// Creates a slots processor whose sink records the slots of every submission.
+    fn create_slots_processor(
+        index_batch: Option<IndexBatchConfig>,
+    ) -> (SlotsProcessor<BoxTransport>, Submissions) {
+        let submissions = Arc::new(StdMutex::new(vec![]));
+        let mut sink = MockSink::new();
+        let batch_submissions = submissions.clone();
+        let block_submissions = submissions.clone();
+
+        sink.expect_index_batch().returning(move |requests| {
+            let slots = requests.iter().map(|request| request.block.slot).collect();
+
+            batch_submissions.lock().unwrap().push(slots);
+
+            Box::pin(async { Ok(()) })
+        });
+        sink.expect_index().returning(move |block, _, _| {
+            block_submissions.lock().unwrap().push(vec![block.slot]);
+
+            Box::pin(async { Ok(()) })
+        });
+
+        let context = SinkContext::new(sink, index_batch);
+
+        (SlotsProcessor::new(Box::new(context), None), submissions)
+    }
+
+    fn batch_config(max_blocks: usize, max_bytes: usize) -> IndexBatchConfig {
+        IndexBatchConfig {
+            max_blocks,
+            max_bytes,
+            max_delay: Duration::from_millis(50),
+        }
+    }
+
+    #[tokio::test]
+    async fn submits_fetched_blocks_in_batches_of_max_blocks() {
+        let (mut slots_processor, submissions) =
+            create_slots_processor(Some(batch_config(3, usize::MAX)));
+        let (tx, rx) = mpsc::channel(8);
+
+        for slot in 0..7 {
+            tx.send(fetched_slot(slot)).await.unwrap();
+        }
+
+        drop(tx);
+
+        slots_processor.submit_slots(0, 7, rx).await.unwrap();
+
+        assert_eq!(
+            *submissions.lock().unwrap(),
+            vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]
+        );
+        assert_eq!(
+            slots_processor.last_processed_block.map(|block| block.slot),
+            Some(6)
+        );
+    }
+
+    #[tokio::test]
+    async fn splits_batches_exceeding_max_bytes() {
+        // The last request is the largest one, so any two requests fit in a batch but not three
+        let request_bytes = serde_json::to_vec(&index_request(2)).unwrap().len();
+        let (mut slots_processor, submissions) =
+            create_slots_processor(Some(batch_config(10, request_bytes * 2)));
+        let (tx, rx) = mpsc::channel(8);
+
+        for slot in 0..3 {
+            tx.send(fetched_slot(slot)).await.unwrap();
+        }
+
+        drop(tx);
+
+        slots_processor.submit_slots(0, 3, rx).await.unwrap();
+
+        assert_eq!(*submissions.lock().unwrap(), vec![vec![0, 1], vec![2]]);
+    }
+
+    #[tokio::test]
+    async fn submits_incomplete_batch_once_no_block_is_fetched_within_max_delay() {
+        let (mut slots_processor, submissions) =
+            create_slots_processor(Some(batch_config(10, usize::MAX)));
+        let (tx, rx) = mpsc::channel(8);
+        let submitter_submissions = submissions.clone();
+
+        let fetcher = tokio::spawn(async move {
+            tx.send(fetched_slot(0)).await.unwrap();
+            tx.send(fetched_slot(1)).await.unwrap();
+
+            tokio::time::sleep(Duration::from_millis(200)).await;
+
+            // The first blocks were submitted while waiting for the next one
+            assert_eq!(*submitter_submissions.lock().unwrap(), vec![vec![0, 1]]);
+
+            tx.send(fetched_slot(2)).await.unwrap();
+        });
+
+        slots_processor.submit_slots(0, 3, rx).await.unwrap();
+        fetcher.await.unwrap();
+
+        assert_eq!(*submissions.lock().unwrap(), vec![vec![0, 1], vec![2]]);
+    }
+
+    #[tokio::test]
+    async fn submits_blocks_one_by_one_without_batches() {
+        let (mut slots_processor, submissions) = create_slots_processor(None);
+        let (tx, rx) = mpsc::channel(8);
+
+        for slot in 0..3 {
+            tx.send(fetched_slot(slot)).await.unwrap();
+        }
+
+        drop(tx);
+
+        slots_processor.submit_slots(0, 3, rx).await.unwrap();
+
+        assert_eq!(
+            *submissions.lock().unwrap(),
+            vec![vec![0], vec![1], vec![2]]
+        );
+    }
+
+    #[tokio::test]
+    async fn submits_blocks_before_failed_fetch() {
+        let (mut slots_processor, submissions) =
+            create_slots_processor(Some(batch_config(10, usize::MAX)));
+        let (tx, rx) = mpsc::channel(8);
+
+        tx.send(fetched_slot(0)).await.unwrap();
+        tx.send(fetched_slot(1)).await.unwrap();
+        tx.send((2, Err(anyhow!("Failed to fetch slot").into())))
+            .await
+            .unwrap();
+        tx.send(fetched_slot(3)).await.unwrap();
+        drop(tx);
+
+        let result = slots_processor.submit_slots(0, 4, rx).await;
+
+        assert!(matches!(
+            result,
+            Err(SlotsProcessorError::FailedSlotsProcessing { failed_slot: 2, .. })
+        ));
+        assert_eq!(*submissions.lock().unwrap(), vec![vec![0, 1]]);
+    }
+
+    #[tokio::test]
+    async fn delivers_fetched_slots_in_order() {
+        // Later slots are fetched faster than the earlier ones
+        let (mut fetched_slots, fetch_task) =
+            spawn_slots_fetching((0..8).collect(), |slot| async move {
+                tokio::time::sleep(Duration::from_millis(10 * (8 - slot as u64))).await;
+
+                slot * 2
+            });
+        let mut received_slots = vec![];
+
+        while let Some((slot, value)) = fetched_slots.recv().await {
+            assert_eq!(value, slot * 2);
+
+            received_slots.push(slot);
+        }
+
+        fetch_task.await.unwrap();
+
+        assert_eq!(received_slots, (0..8).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn fetches_upcoming_slots_while_submitting() {
+        let fetched = Arc::new(AtomicUsize::new(0));
+        let fetch_counter = fetched.clone();
+        let (mut fetched_slots, fetch_task) = spawn_slots_fetching((0..8).collect(), move |slot| {
+            let fetch_counter = fetch_counter.clone();
+
+            async move {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                fetch_counter.fetch_add(1, Ordering::SeqCst);
+
+                slot
+            }
+        });
+
+        assert_eq!(fetched_slots.recv().await.map(|(slot, _)| slot), Some(0));
+
+        // Submitting the first slot takes longer than fetching several others
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert!(fetched.load(Ordering::SeqCst) > PIPELINE_DEPTH);
+
+        drop(fetched_slots);
+        fetch_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn delivers_slots_up_to_failed_fetch_and_stops_fetching() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let fetch_counter = started.clone();
+        let (mut fetched_slots, fetch_task) =
+            spawn_slots_fetching((0..100).collect(), move |slot| {
+                fetch_counter.fetch_add(1, Ordering::SeqCst);
+
+                async move {
+                    // The failed slot resolves before the slots preceding it
+                    if slot == 2 {
+                        return Err(format!("Failed to fetch slot {slot}"));
+                    }
+
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+
+                    Ok(slot)
+                }
+            });
+        let mut received = vec![];
+
+        while let Some((slot, result)) = fetched_slots.recv().await {
+            let failed = result.is_err();
+
+            received.push((slot, result));
+
+            if failed {
+                break;
+            }
+        }
+
+        drop(fetched_slots);
+        tokio::time::timeout(Duration::from_secs(5), fetch_task)
+            .await
+            .expect("Fetching should stop once the receiver is dropped")
+            .unwrap();
+
+        assert_eq!(
+            received,
+            vec![
+                (0, Ok(0)),
+                (1, Ok(1)),
+                (2, Err("Failed to fetch slot 2".to_string()))
+            ]
+        );
+        assert!(started.load(Ordering::SeqCst) < 100);
+    }
+}
@@ -11,6 +11,8 @@ pub enum SynchronizerError {
         initial_slot: u32,
         final_slot: u32,
         chunk_errors: SlotsChunksErrors,
+        /// Initial and final slot of each failed thread
+        failed_chunks: Vec<(u32, u32)>,
     },
     #[error(transparent)]
     FailedBlockIdResolution(#[from] BlockIdResolutionError),
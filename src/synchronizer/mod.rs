@@ -1,11 +1,10 @@
 use std::fmt::Debug;
 
-use alloy::transports::http::ReqwestTransport;
+use alloy::transports::Transport;
 use anyhow::anyhow;
 use async_trait::async_trait;
-use futures::future::join_all;
-use tokio::task::JoinHandle;
-use tracing::{debug, info, Instrument};
+use futures::{stream::FuturesUnordered, Future, StreamExt};
+use tracing::{debug, error, info, warn, Instrument};
 
 #[cfg(test)]
 use mockall::automock;
@@ -13,9 +12,10 @@ use mockall::automock;
 use crate::{
     clients::{
         beacon::types::{BlockHeader, BlockId, BlockIdResolution},
-        blobscan::types::BlockchainSyncState,
+        blobscan::types::{BlockchainSyncState, FailedSlotsChunk},
     },
     context::CommonContext,
+    metrics,
     slots_processor::{error::SlotsProcessorError, SlotsProcessor},
 };
 
@@ -23,6 +23,9 @@ use self::error::{SlotsChunksErrors, SynchronizerError};
 
 pub mod error;
 
+/// Slots processed by each worker unless index batches need larger ranges to fill up.
+pub const DEFAULT_SLOTS_PER_BATCH: u32 = 10;
+
 #[async_trait]
 #[cfg_attr(test, automock)]
 pub trait CommonSynchronizer: Send + Sync {
@@ -39,19 +42,47 @@ pub trait CommonSynchronizer: Send + Sync {
 #[derive(Debug)]
 pub struct SynchronizerBuilder {
     num_threads: u32,
-    min_slots_per_thread: u32,
+    slots_per_batch: u32,
     slots_checkpoint: u32,
     checkpoint_type: CheckpointType,
     last_synced_block: Option<BlockHeader>,
+    track_failed_chunks: bool,
 }
 
 pub struct Synchronizer<T> {
     context: Box<dyn CommonContext<T>>,
     num_threads: u32,
-    min_slots_per_thread: u32,
+    slots_per_batch: u32,
     slots_checkpoint: u32,
     checkpoint_type: CheckpointType,
     last_synced_block: Option<BlockHeader>,
+    track_failed_chunks: bool,
+}
+
+/// Slots processed by a single worker, part of a checkpoint chunk.
+#[derive(Clone, Copy, Debug)]
+struct SlotsBatch {
+    chunk: usize,
+    /// Position of the batch within its chunk
+    index: usize,
+    initial_slot: u32,
+    final_slot: u32,
+}
+
+/// Batches left to process in each checkpoint chunk. Batches may complete in any order, but
+/// checkpoints are only handed out for the completed chunks following the last checkpoint.
+///
+/// The first batch of a chunk continues from the block checkpointed for the previous chunk, so
+/// reorgs are detected across chunks, and therefore waits for that checkpoint to start.
+struct ChunksProgress {
+    pending_batches: Vec<usize>,
+    /// Last block processed in each chunk, along with the position of the batch it belongs to
+    last_blocks: Vec<Option<(usize, BlockHeader)>>,
+    /// Whether each chunk had batches queued to be retried instead of processed
+    skipped_batches: Vec<bool>,
+    next_checkpoint_chunk: usize,
+    /// Block the first batch of the next chunk to start continues from
+    checkpointed_block: Option<BlockHeader>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -65,10 +96,11 @@ impl Default for SynchronizerBuilder {
     fn default() -> Self {
         SynchronizerBuilder {
             num_threads: 1,
-            min_slots_per_thread: 50,
+            slots_per_batch: DEFAULT_SLOTS_PER_BATCH,
             slots_checkpoint: 1000,
             checkpoint_type: CheckpointType::Upper,
             last_synced_block: None,
+            track_failed_chunks: false,
         }
     }
 }
@@ -90,6 +122,12 @@ impl SynchronizerBuilder {
         self
     }
 
+    pub fn with_slots_per_batch(&mut self, slots_per_batch: u32) -> &mut Self {
+        self.slots_per_batch = slots_per_batch;
+
+        self
+    }
+
     pub fn with_slots_checkpoint(&mut self, slots_checkpoint: u32) -> &mut Self {
         self.slots_checkpoint = slots_checkpoint;
         self
@@ -101,226 +139,314 @@ impl SynchronizerBuilder {
         self
     }
 
-    pub fn build(
-        &self,
-        context: Box<dyn CommonContext<ReqwestTransport>>,
-    ) -> Synchronizer<ReqwestTransport> {
+    /// Queues the slot ranges that fail to be processed to be retried later and keeps syncing
+    /// past them, instead of aborting the sync.
+    pub fn with_failed_chunks_tracking(&mut self, track_failed_chunks: bool) -> &mut Self {
+        self.track_failed_chunks = track_failed_chunks;
+
+        self
+    }
+
+    pub fn build<T>(&self, context: Box<dyn CommonContext<T>>) -> Synchronizer<T> {
         Synchronizer {
             context,
             num_threads: self.num_threads,
-            min_slots_per_thread: self.min_slots_per_thread,
+            slots_per_batch: self.slots_per_batch,
             slots_checkpoint: self.slots_checkpoint,
             checkpoint_type: self.checkpoint_type,
             last_synced_block: self.last_synced_block.clone(),
+            track_failed_chunks: self.track_failed_chunks,
         }
     }
 }
 
-impl Synchronizer<ReqwestTransport> {
-    async fn process_slots(
+impl<T> Synchronizer<T>
+where
+    T: Transport + Clone + Send + Sync + 'static,
+{
+    async fn process_slots_by_checkpoints(
         &mut self,
-        from_slot: u32,
-        to_slot: u32,
+        initial_slot: u32,
+        final_slot: u32,
     ) -> Result<(), SynchronizerError> {
-        let is_reverse_sync = to_slot < from_slot;
-        let unprocessed_slots = to_slot.abs_diff(from_slot);
-        let min_slots_per_thread = std::cmp::min(unprocessed_slots, self.min_slots_per_thread);
-        let slots_per_thread =
-            std::cmp::max(min_slots_per_thread, unprocessed_slots / self.num_threads);
-        let num_threads = std::cmp::max(1, unprocessed_slots / slots_per_thread);
-        let remaining_slots = unprocessed_slots % num_threads;
-
-        let mut handles: Vec<JoinHandle<Result<Option<BlockHeader>, SlotsProcessorError>>> = vec![];
-
-        for i in 0..num_threads {
-            let is_first_thread = i == 0;
-            let is_last_thread = i == num_threads - 1;
-            let thread_total_slots =
-                slots_per_thread + if is_last_thread { remaining_slots } else { 0 };
-            let thread_initial_slot = if is_reverse_sync {
-                from_slot - i * slots_per_thread
-            } else {
-                from_slot + i * slots_per_thread
-            };
-            let thread_final_slot = if is_reverse_sync {
-                thread_initial_slot - thread_total_slots
-            } else {
-                thread_initial_slot + thread_total_slots
-            };
+        let unprocessed_slots = final_slot.abs_diff(initial_slot);
 
-            let synchronizer_thread_span = tracing::debug_span!(
-                parent:  &tracing::Span::current(),
-                "thread",
-                thread = i,
-                chunk_initial_slot = thread_initial_slot,
-                chunk_final_slot = thread_final_slot
+        if unprocessed_slots == 1 {
+            info!(slot = initial_slot, "Syncing {unprocessed_slots} slot…");
+        } else {
+            info!(
+                initial_slot,
+                final_slot, "Syncing {unprocessed_slots} slots…"
             );
+        }
 
-            let last_processed_block_header = if is_first_thread {
-                self.last_synced_block.clone()
-            } else {
-                None
-            };
-            let mut slots_processor =
-                SlotsProcessor::new(self.context.clone(), last_processed_block_header);
+        // Checkpoints are saved once every batch of a chunk and the chunks before it are done
+        let chunks =
+            split_slots_range(initial_slot, final_slot, self.slots_checkpoint).collect::<Vec<_>>();
+        let slots_per_batch = self.slots_per_batch;
+        let mut batches = chunks
+            .clone()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(chunk, (initial_chunk_slot, final_chunk_slot))| {
+                split_slots_range(initial_chunk_slot, final_chunk_slot, slots_per_batch)
+                    .enumerate()
+                    .map(move |(index, (initial_slot, final_slot))| SlotsBatch {
+                        chunk,
+                        index,
+                        initial_slot,
+                        final_slot,
+                    })
+            })
+            .peekable();
+        let mut progress =
+            ChunksProgress::new(&chunks, slots_per_batch, self.last_synced_block.clone());
+        let mut started_chunks = 0;
+        let mut in_flight_batches = FuturesUnordered::new();
+        let mut errors = vec![];
+        let mut failed_chunks = vec![];
+
+        loop {
+            // Stop handing out batches after a failure and wait for the in-flight ones
+            while errors.is_empty() && in_flight_batches.len() < self.num_threads.max(1) as usize {
+                let Some(batch) = batches.next_if(|batch| progress.can_start(batch)) else {
+                    break;
+                };
+
+                if batch.index == 0 {
+                    started_chunks += 1;
 
-            let handle = tokio::spawn(
-                async move {
-                    slots_processor
-                        .process_slots(thread_initial_slot, thread_final_slot)
-                        .await?;
+                    if let Some(journal) = self.context.journal() {
+                        let (initial_chunk_slot, final_chunk_slot) = chunks[batch.chunk];
 
-                    Ok(slots_processor.last_processed_block)
+                        journal
+                            .start_chunk(initial_chunk_slot, final_chunk_slot)
+                            .await?;
+                    }
                 }
-                .instrument(synchronizer_thread_span)
-                .in_current_span(),
-            );
 
-            handles.push(handle);
-        }
+                in_flight_batches.push(self.spawn_batch(batch, progress.starting_block(&batch)));
+            }
 
-        let handle_outputs = join_all(handles).await;
+            let Some((batch, result)) = in_flight_batches.next().await else {
+                break;
+            };
 
-        let mut errors = vec![];
-        let mut last_thread_block: Option<BlockHeader> = None;
-
-        for handle in handle_outputs {
-            match handle {
-                Ok(thread_result) => match thread_result {
-                    Ok(thread_block_header) => {
-                        if let Some(block_header) = thread_block_header {
-                            last_thread_block = Some(block_header);
-                        }
-                    }
-                    Err(error) => errors.push(error),
-                },
+            match result {
+                Ok(last_block) => progress.complete_batch(&batch, last_block),
                 Err(error) => {
-                    let err = anyhow!("Synchronizer thread panicked: {:?}", error);
+                    if !self.track_failed_chunks || !self.queue_failed_batch(&batch, &error).await {
+                        errors.push(error);
+                        failed_chunks.push((batch.initial_slot, batch.final_slot));
 
-                    errors.push(err.into());
+                        continue;
+                    }
+
+                    progress.skip_batch(&batch);
                 }
             }
+
+            self.save_completed_checkpoints(&chunks, &mut progress)
+                .await?;
         }
 
         if !errors.is_empty() {
+            // The chunks left unfinished will be synced again from the last checkpoint
+            if let Some(journal) = self.context.journal() {
+                for &(initial_chunk_slot, final_chunk_slot) in
+                    &chunks[progress.next_checkpoint_chunk..started_chunks]
+                {
+                    if let Err(error) = journal
+                        .abandon_chunk(initial_chunk_slot, final_chunk_slot)
+                        .await
+                    {
+                        warn!(
+                            ?error,
+                            initial_chunk_slot,
+                            final_chunk_slot,
+                            "Failed to remove unfinished chunk from the journal"
+                        );
+                    }
+                }
+            }
+
             return Err(SynchronizerError::FailedParallelSlotsProcessing {
-                initial_slot: from_slot,
-                final_slot: to_slot,
+                initial_slot,
+                final_slot,
                 chunk_errors: SlotsChunksErrors(errors),
+                failed_chunks,
             });
         }
 
-        if let Some(last_thread_block) = last_thread_block {
-            self.last_synced_block = Some(last_thread_block);
+        Ok(())
+    }
+
+    /// Processes a batch of slots in its own task, resolving to the batch along with the last
+    /// block processed.
+    fn spawn_batch(
+        &self,
+        batch: SlotsBatch,
+        last_processed_block: Option<BlockHeader>,
+    ) -> impl Future<Output = (SlotsBatch, Result<Option<BlockHeader>, SlotsProcessorError>)> {
+        let batch_span = tracing::debug_span!(
+            parent: &tracing::Span::current(),
+            "batch",
+            batch_initial_slot = batch.initial_slot,
+            batch_final_slot = batch.final_slot
+        );
+        let mut slots_processor = SlotsProcessor::new(self.context.clone(), last_processed_block);
+        let handle = tokio::spawn(
+            async move {
+                slots_processor
+                    .process_slots(batch.initial_slot, batch.final_slot)
+                    .await?;
+
+                Ok(slots_processor.last_processed_block)
+            }
+            .instrument(batch_span),
+        );
+
+        async move {
+            let result = match handle.await {
+                Ok(result) => result,
+                Err(error) => Err(anyhow!("Synchronizer thread panicked: {:?}", error).into()),
+            };
+
+            (batch, result)
+        }
+    }
+
+    /// Saves the checkpoints of the chunks whose batches and preceding chunks are all done.
+    async fn save_completed_checkpoints(
+        &mut self,
+        chunks: &[(u32, u32)],
+        progress: &mut ChunksProgress,
+    ) -> Result<(), SynchronizerError> {
+        while let Some((chunk, last_block)) = progress.next_checkpoint() {
+            if let Some(block_header) = last_block {
+                self.last_synced_block = Some(block_header);
+            }
+
+            let (initial_chunk_slot, final_chunk_slot) = chunks[chunk];
+
+            self.save_checkpoint(initial_chunk_slot, final_chunk_slot)
+                .await?;
         }
 
         Ok(())
     }
 
-    async fn process_slots_by_checkpoints(
-        &mut self,
-        initial_slot: u32,
-        final_slot: u32,
+    /// Saves the sync state once the slots of a chunk and the chunks before it are processed.
+    async fn save_checkpoint(
+        &self,
+        initial_chunk_slot: u32,
+        final_chunk_slot: u32,
     ) -> Result<(), SynchronizerError> {
-        let is_reverse_sync = final_slot < initial_slot;
-        let mut current_slot = initial_slot;
-        let mut unprocessed_slots = final_slot.abs_diff(current_slot);
+        if self.checkpoint_type == CheckpointType::Disabled {
+            if let Some(journal) = self.context.journal() {
+                journal
+                    .complete_chunk(initial_chunk_slot, final_chunk_slot, None)
+                    .await?;
+            }
 
-        if unprocessed_slots == 1 {
-            info!(slot = initial_slot, "Syncing {unprocessed_slots} slot…");
+            return Ok(());
+        }
+
+        let last_slot = Some(if final_chunk_slot < initial_chunk_slot {
+            final_chunk_slot + 1
         } else {
-            info!(
-                initial_slot,
-                final_slot, "Syncing {unprocessed_slots} slots…"
-            );
+            final_chunk_slot - 1
+        });
+        let mut last_lower_synced_slot = None;
+        let mut last_upper_synced_slot = None;
+        let mut last_upper_synced_block_root = None;
+        let mut last_upper_synced_block_slot = None;
+
+        if self.checkpoint_type == CheckpointType::Lower {
+            last_lower_synced_slot = last_slot;
+        } else if self.checkpoint_type == CheckpointType::Upper {
+            last_upper_synced_slot = last_slot;
+            last_upper_synced_block_root = self.last_synced_block.as_ref().map(|block| block.root);
+            last_upper_synced_block_slot = self.last_synced_block.as_ref().map(|block| block.slot);
         }
 
-        while unprocessed_slots > 0 {
-            let slots_chunk = std::cmp::min(unprocessed_slots, self.slots_checkpoint);
-            let initial_chunk_slot = current_slot;
-            let final_chunk_slot = if is_reverse_sync {
-                current_slot - slots_chunk
-            } else {
-                current_slot + slots_chunk
+        let sync_state = BlockchainSyncState {
+            last_finalized_block: None,
+            last_lower_synced_slot,
+            last_upper_synced_slot,
+            last_upper_synced_block_root,
+            last_upper_synced_block_slot,
+        };
+
+        // The journal is updated first so the position isn't lost if the sink fails
+        if let Some(journal) = self.context.journal() {
+            journal
+                .complete_chunk(
+                    initial_chunk_slot,
+                    final_chunk_slot,
+                    Some(sync_state.clone()),
+                )
+                .await?;
+        }
+
+        if let Err(error) = self.context.sink().update_sync_state(sync_state).await {
+            let new_synced_slot = match last_lower_synced_slot.or(last_upper_synced_slot) {
+                Some(slot) => slot,
+                None => return Err(SynchronizerError::Other(anyhow!(
+                    "Failed to get new last synced slot: last_lower_synced_slot and last_upper_synced_slot are both None"
+                )))
             };
 
-            let sync_slots_chunk_span = tracing::debug_span!(
-                parent: &tracing::Span::current(),
-                "checkpoint",
-                checkpoint_initial_slot = initial_chunk_slot,
-                checkpoint_final_slot = final_chunk_slot
-            );
+            return Err(SynchronizerError::FailedSlotCheckpointSave {
+                slot: new_synced_slot,
+                error,
+            });
+        }
 
-            self.process_slots(initial_chunk_slot, final_chunk_slot)
-                .instrument(sync_slots_chunk_span)
-                .await?;
+        if let Some(slot) = last_lower_synced_slot {
+            metrics::LAST_SYNCED_SLOT
+                .with_label_values(&["lower"])
+                .set(slot.into());
+        }
 
-            let last_slot = Some(if is_reverse_sync {
-                final_chunk_slot + 1
-            } else {
-                final_chunk_slot - 1
-            });
+        if let Some(slot) = last_upper_synced_slot {
+            metrics::LAST_SYNCED_SLOT
+                .with_label_values(&["upper"])
+                .set(slot.into());
+        }
 
-            if self.checkpoint_type != CheckpointType::Disabled {
-                let mut last_lower_synced_slot = None;
-                let mut last_upper_synced_slot = None;
-                let mut last_upper_synced_block_root = None;
-                let mut last_upper_synced_block_slot = None;
-
-                if self.checkpoint_type == CheckpointType::Lower {
-                    last_lower_synced_slot = last_slot;
-                } else if self.checkpoint_type == CheckpointType::Upper {
-                    last_upper_synced_slot = last_slot;
-                    last_upper_synced_block_root =
-                        self.last_synced_block.as_ref().map(|block| block.root);
-                    last_upper_synced_block_slot =
-                        self.last_synced_block.as_ref().map(|block| block.slot);
-                }
+        debug!(
+            new_last_lower_synced_slot = last_lower_synced_slot,
+            new_last_upper_synced_slot = last_upper_synced_slot,
+            "Checkpoint reached. Last synced slot saved…"
+        );
 
-                if let Err(error) = self
-                    .context
-                    .blobscan_client()
-                    .update_sync_state(BlockchainSyncState {
-                        last_finalized_block: None,
-                        last_lower_synced_slot,
-                        last_upper_synced_slot,
-                        last_upper_synced_block_root,
-                        last_upper_synced_block_slot,
-                    })
-                    .await
-                {
-                    let new_synced_slot = match last_lower_synced_slot.or(last_upper_synced_slot) {
-                        Some(slot) => slot,
-                        None => return Err(SynchronizerError::Other(anyhow!(
-                            "Failed to get new last synced slot: last_lower_synced_slot and last_upper_synced_slot are both None"
-                        )))
-                    };
-
-                    return Err(SynchronizerError::FailedSlotCheckpointSave {
-                        slot: new_synced_slot,
-                        error,
-                    });
-                }
+        Ok(())
+    }
 
-                if unprocessed_slots >= self.slots_checkpoint {
-                    debug!(
-                        new_last_lower_synced_slot = last_lower_synced_slot,
-                        new_last_upper_synced_slot = last_upper_synced_slot,
-                        "Checkpoint reached. Last synced slot saved…"
-                    );
-                }
-            }
+    /// Queues a failed batch to be retried later, returning whether it could be queued.
+    async fn queue_failed_batch(&self, batch: &SlotsBatch, error: &SlotsProcessorError) -> bool {
+        let failed_chunk = FailedSlotsChunk::from((batch.initial_slot, batch.final_slot));
 
-            current_slot = if is_reverse_sync {
-                current_slot - slots_chunk
-            } else {
-                current_slot + slots_chunk
-            };
+        if let Err(queue_error) = self
+            .context
+            .sink()
+            .add_failed_slots_chunks(vec![failed_chunk])
+            .await
+        {
+            error!(?queue_error, "Failed to queue failed slots chunk");
 
-            unprocessed_slots -= slots_chunk;
+            return false;
         }
 
-        Ok(())
+        metrics::FAILED_SLOTS_CHUNKS.inc();
+
+        warn!(
+            %error,
+            "Failed to process some slots. Queued them to be retried and continuing…"
+        );
+
+        true
     }
 
     pub fn clear_last_synced_block(&mut self) {
@@ -329,7 +455,10 @@ impl Synchronizer<ReqwestTransport> {
 }
 
 #[async_trait]
-impl CommonSynchronizer for Synchronizer<ReqwestTransport> {
+impl<T> CommonSynchronizer for Synchronizer<T>
+where
+    T: Transport + Clone + Send + Sync + 'static,
+{
     fn clear_last_synced_block(&mut self) {
         self.clear_last_synced_block();
     }
@@ -381,3 +510,322 @@ impl CommonSynchronizer for Synchronizer<ReqwestTransport> {
         }
     }
 }
+
+impl ChunksProgress {
+    fn new(
+        chunks: &[(u32, u32)],
+        slots_per_batch: u32,
+        last_synced_block: Option<BlockHeader>,
+    ) -> Self {
+        let pending_batches = chunks
+            .iter()
+            .map(|(initial_slot, final_slot)| {
+                final_slot.abs_diff(*initial_slot).div_ceil(slots_per_batch) as usize
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            last_blocks: vec![None; pending_batches.len()],
+            skipped_batches: vec![false; pending_batches.len()],
+            pending_batches,
+            next_checkpoint_chunk: 0,
+            checkpointed_block: last_synced_block,
+        }
+    }
+
+    /// Returns whether a batch can be processed, which for the first batch of a chunk requires
+    /// the previous chunks to be checkpointed.
+    fn can_start(&self, batch: &SlotsBatch) -> bool {
+        batch.index > 0 || batch.chunk <= self.next_checkpoint_chunk
+    }
+
+    /// Returns the block a batch continues from, if it's the first one of its chunk.
+    fn starting_block(&self, batch: &SlotsBatch) -> Option<BlockHeader> {
+        if batch.index == 0 {
+            self.checkpointed_block.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Marks a batch as done, keeping its last block if no later batch of the chunk finished yet.
+    fn complete_batch(&mut self, batch: &SlotsBatch, last_block: Option<BlockHeader>) {
+        if let Some(block_header) = last_block {
+            let chunk_last_block = &mut self.last_blocks[batch.chunk];
+
+            if chunk_last_block
+                .as_ref()
+                .is_none_or(|(index, _)| batch.index > *index)
+            {
+                *chunk_last_block = Some((batch.index, block_header));
+            }
+        }
+
+        self.pending_batches[batch.chunk] -= 1;
+    }
+
+    /// Marks a batch queued to be retried as done. Its blocks are missing, so the next chunk
+    /// can't be checked against the last block of its chunk.
+    fn skip_batch(&mut self, batch: &SlotsBatch) {
+        self.skipped_batches[batch.chunk] = true;
+        self.pending_batches[batch.chunk] -= 1;
+    }
+
+    /// Returns the next chunk ready to be checkpointed, along with its last processed block.
+    fn next_checkpoint(&mut self) -> Option<(usize, Option<BlockHeader>)> {
+        let chunk = self.next_checkpoint_chunk;
+
+        if self
+            .pending_batches
+            .get(chunk)
+            .is_none_or(|pending| *pending > 0)
+        {
+            return None;
+        }
+
+        self.next_checkpoint_chunk += 1;
+
+        let last_block = self.last_blocks[chunk]
+            .take()
+            .map(|(_, block_header)| block_header);
+
+        if self.skipped_batches[chunk] {
+            self.checkpointed_block = None;
+        } else if let Some(block_header) = &last_block {
+            self.checkpointed_block = Some(block_header.clone());
+        }
+
+        Some((chunk, last_block))
+    }
+}
+
+/// Splits a slot range into consecutive ranges of up to `size` slots, in syncing order.
+fn split_slots_range(
+    initial_slot: u32,
+    final_slot: u32,
+    size: u32,
+) -> impl Iterator<Item = (u32, u32)> {
+    let is_reverse_sync = final_slot < initial_slot;
+    let total_slots = final_slot.abs_diff(initial_slot);
+
+    (0..total_slots.div_ceil(size)).map(move |i| {
+        let offset = i * size;
+        let range_slots = size.min(total_slots - offset);
+
+        if is_reverse_sync {
+            (initial_slot - offset, initial_slot - offset - range_slots)
+        } else {
+            (initial_slot + offset, initial_slot + offset + range_slots)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{primitives::B256, transports::BoxTransport};
+    use mockall::{predicate::eq, Sequence};
+
+    use crate::{context::SinkContext, sinks::MockSink};
+
+    use super::*;
+
+    fn block(slot: u32) -> BlockHeader {
+        BlockHeader {
+            root: B256::repeat_byte(slot as u8),
+            parent_root: B256::ZERO,
+            slot,
+        }
+    }
+
+    fn batch(chunk: usize, index: usize) -> SlotsBatch {
+        SlotsBatch {
+            chunk,
+            index,
+            initial_slot: 0,
+            final_slot: 0,
+        }
+    }
+
+    fn checkpoint(last_upper_synced_slot: u32, last_block: &BlockHeader) -> BlockchainSyncState {
+        BlockchainSyncState {
+            last_finalized_block: None,
+            last_lower_synced_slot: None,
+            last_upper_synced_slot: Some(last_upper_synced_slot),
+            last_upper_synced_block_root: Some(last_block.root),
+            last_upper_synced_block_slot: Some(last_block.slot),
+        }
+    }
+
+    #[test]
+    fn splits_slots_range_forwards() {
+        let ranges = split_slots_range(100, 125, 10).collect::<Vec<_>>();
+
+        assert_eq!(ranges, vec![(100, 110), (110, 120), (120, 125)]);
+    }
+
+    #[test]
+    fn splits_slots_range_backwards() {
+        let ranges = split_slots_range(125, 100, 10).collect::<Vec<_>>();
+
+        assert_eq!(ranges, vec![(125, 115), (115, 105), (105, 100)]);
+    }
+
+    #[test]
+    fn splits_slots_range_into_exact_and_empty_ranges() {
+        assert_eq!(
+            split_slots_range(100, 120, 10).collect::<Vec<_>>(),
+            vec![(100, 110), (110, 120)]
+        );
+        assert_eq!(split_slots_range(100, 100, 10).count(), 0);
+        assert_eq!(
+            split_slots_range(100, 99, 10).collect::<Vec<_>>(),
+            vec![(100, 99)]
+        );
+    }
+
+    #[test]
+    fn checkpoints_chunks_in_order_when_batches_complete_out_of_order() {
+        let chunks = split_slots_range(0, 50, 20).collect::<Vec<_>>();
+        let mut progress = ChunksProgress::new(&chunks, 10, None);
+
+        assert_eq!(progress.pending_batches, vec![2, 2, 1]);
+
+        progress.complete_batch(&batch(2, 0), Some(block(49)));
+        progress.complete_batch(&batch(1, 1), Some(block(39)));
+        progress.complete_batch(&batch(1, 0), Some(block(29)));
+
+        assert!(progress.next_checkpoint().is_none());
+
+        progress.complete_batch(&batch(0, 1), Some(block(19)));
+        // An earlier batch finishing later doesn't replace the last block of the chunk
+        progress.complete_batch(&batch(0, 0), Some(block(9)));
+
+        let checkpoints = std::iter::from_fn(|| progress.next_checkpoint())
+            .map(|(chunk, last_block)| (chunk, last_block.map(|block| block.slot)))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            checkpoints,
+            vec![(0, Some(19)), (1, Some(39)), (2, Some(49))]
+        );
+        assert!(progress.next_checkpoint().is_none());
+    }
+
+    #[test]
+    fn stops_checkpointing_at_chunk_with_failed_batch() {
+        let chunks = split_slots_range(50, 0, 20).collect::<Vec<_>>();
+        let mut progress = ChunksProgress::new(&chunks, 10, None);
+
+        progress.complete_batch(&batch(0, 0), Some(block(41)));
+        progress.complete_batch(&batch(0, 1), Some(block(31)));
+        // The first batch of the middle chunk fails and is never completed
+        progress.complete_batch(&batch(1, 1), Some(block(11)));
+        progress.complete_batch(&batch(2, 0), Some(block(1)));
+
+        let (chunk, last_block) = progress.next_checkpoint().unwrap();
+
+        assert_eq!(chunk, 0);
+        assert_eq!(last_block.map(|block| block.slot), Some(31));
+        assert!(progress.next_checkpoint().is_none());
+    }
+
+    #[test]
+    fn checkpoints_chunks_with_queued_failed_batches() {
+        let chunks = split_slots_range(0, 20, 10).collect::<Vec<_>>();
+        let mut progress = ChunksProgress::new(&chunks, 10, None);
+
+        progress.skip_batch(&batch(0, 0));
+        progress.complete_batch(&batch(1, 0), Some(block(19)));
+
+        assert!(matches!(progress.next_checkpoint(), Some((0, None))));
+        assert!(matches!(progress.next_checkpoint(), Some((1, Some(_)))));
+    }
+
+    #[test]
+    fn continues_each_chunk_from_the_previous_checkpointed_block() {
+        let chunks = split_slots_range(0, 40, 20).collect::<Vec<_>>();
+        let mut progress = ChunksProgress::new(&chunks, 10, Some(block(0)));
+
+        assert!(progress.can_start(&batch(0, 0)));
+        assert_eq!(
+            progress
+                .starting_block(&batch(0, 0))
+                .map(|block| block.slot),
+            Some(0)
+        );
+        assert!(progress.can_start(&batch(0, 1)));
+        assert!(progress.starting_block(&batch(0, 1)).is_none());
+
+        // The second chunk only starts once the first one is checkpointed
+        assert!(!progress.can_start(&batch(1, 0)));
+
+        progress.complete_batch(&batch(0, 1), Some(block(19)));
+        progress.complete_batch(&batch(0, 0), Some(block(9)));
+
+        assert!(!progress.can_start(&batch(1, 0)));
+        assert!(progress.next_checkpoint().is_some());
+        assert!(progress.can_start(&batch(1, 0)));
+        assert_eq!(
+            progress
+                .starting_block(&batch(1, 0))
+                .map(|block| block.slot),
+            Some(19)
+        );
+        assert!(progress.starting_block(&batch(1, 1)).is_none());
+    }
+
+    #[test]
+    fn continues_chunk_after_skipped_batches_without_block() {
+        let chunks = split_slots_range(0, 40, 20).collect::<Vec<_>>();
+        let mut progress = ChunksProgress::new(&chunks, 10, Some(block(0)));
+
+        progress.complete_batch(&batch(0, 0), Some(block(9)));
+        progress.skip_batch(&batch(0, 1));
+        progress.next_checkpoint();
+
+        assert!(progress.can_start(&batch(1, 0)));
+        assert!(progress.starting_block(&batch(1, 0)).is_none());
+    }
+
+    #[tokio::test]
+    async fn saves_checkpoints_in_chunk_order() {
+        let mut sink = MockSink::new();
+        let mut sequence = Sequence::new();
+
+        for (last_slot, last_block) in [(19, block(19)), (39, block(39)), (49, block(49))] {
+            sink.expect_update_sync_state()
+                .with(eq(checkpoint(last_slot, &last_block)))
+                .times(1)
+                .in_sequence(&mut sequence)
+                .returning(|_| Box::pin(async { Ok(()) }));
+        }
+
+        let context = Box::new(SinkContext::new(sink, None));
+        let mut synchronizer = SynchronizerBuilder::new()
+            .with_checkpoint_type(CheckpointType::Upper)
+            .build(context as Box<dyn CommonContext<BoxTransport>>);
+        let chunks = split_slots_range(0, 50, 20).collect::<Vec<_>>();
+        let mut progress = ChunksProgress::new(&chunks, 10, None);
+
+        for (chunk, index, last_slot) in [(1, 1, 39), (2, 0, 49), (1, 0, 29), (0, 1, 19)] {
+            progress.complete_batch(&batch(chunk, index), Some(block(last_slot)));
+            synchronizer
+                .save_completed_checkpoints(&chunks, &mut progress)
+                .await
+                .unwrap();
+        }
+
+        assert!(synchronizer.last_synced_block.is_none());
+
+        progress.complete_batch(&batch(0, 0), Some(block(9)));
+        synchronizer
+            .save_completed_checkpoints(&chunks, &mut progress)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            synchronizer.last_synced_block.map(|block| block.slot),
+            Some(49)
+        );
+    }
+}
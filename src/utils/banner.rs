@@ -1,6 +1,12 @@
 use url::Url;
 
-use crate::{args::Args, env::Environment};
+use crate::{
+    args::{Args, Command},
+    blob_storage::BlobStorageType,
+    env::Environment,
+    network::Fork,
+    sinks::SinkType,
+};
 
 fn mask_quik_node_url(url_string: &str) -> Option<String> {
     match Url::parse(url_string) {
@@ -71,21 +77,56 @@ pub fn print_banner(args: &Args, env: &Environment) {
         println!("Dencun fork slot: {}", env.network_name.dencun_fork_slot());
     }
 
-    if let Some(from_slot) = args.from_slot.clone() {
+    let fork_schedule = env.fork_schedule();
+
+    match fork_schedule.fork_slot(Fork::Electra) {
+        Some(electra_fork_slot) => println!("Electra fork slot: {electra_fork_slot}"),
+        None => println!("Electra fork slot: not scheduled"),
+    }
+
+    match fork_schedule.fork_slot(Fork::Fulu) {
+        Some(fulu_fork_slot) => println!("Fulu fork slot: {fulu_fork_slot}"),
+        None => println!("Fulu fork slot: not scheduled"),
+    }
+
+    for (slot, blob_parameters) in env.blob_schedule().iter() {
+        println!(
+            "Blob parameters from slot {slot}: target {}, max {}, update fraction {}, min base fee {}",
+            blob_parameters.target_blobs_per_block,
+            blob_parameters.max_blobs_per_block,
+            blob_parameters.base_fee_update_fraction,
+            blob_parameters.min_base_fee_per_blob_gas
+        );
+    }
+
+    let command = args.command();
+
+    println!("Command: {}", command.name());
+
+    let (from_slot, to_slot) = match command {
+        Command::Sync { from_slot } | Command::Live { from_slot } => (from_slot, None),
+        Command::Backfill { from_slot, to_slot } => (from_slot, to_slot),
+        Command::Repair { from_slot, to_slot } => (Some(from_slot), Some(to_slot)),
+        Command::FailedChunks { .. } | Command::Inspect { .. } | Command::Config { .. } => {
+            (None, None)
+        }
+    };
+
+    if let Some(from_slot) = from_slot {
         println!("Custom start slot: {}", from_slot.to_detailed_string());
     }
 
-    if let Some(to_slot) = args.to_slot.clone() {
+    if let Some(to_slot) = to_slot {
         println!("Custom end slot: {}", to_slot.to_detailed_string());
     }
 
-    if let Some(num_threads) = args.num_threads {
+    if let Some(num_threads) = env.num_threads {
         println!("Number of threads: {}", num_threads);
     } else {
         println!("Number of threads: auto");
     }
 
-    if let Some(slots_per_save) = args.slots_per_save {
+    if let Some(slots_per_save) = env.slots_per_save {
         println!("Slots checkpoint size: {}", slots_per_save);
     } else {
         println!("Slots checkpoint size: 1000");
@@ -93,31 +134,130 @@ pub fn print_banner(args: &Args, env: &Environment) {
 
     println!(
         "Disable sync checkpoint saving: {}",
-        if args.disable_sync_checkpoint_save {
+        if env.disable_sync_checkpoint_save {
             "yes"
         } else {
             "no"
         }
     );
 
+    println!("Dry run: {}", if args.dry_run { "yes" } else { "no" });
     println!(
-        "Disable historical sync: {}",
-        if args.disable_sync_historical {
-            "yes"
+        "Blob analysis: {}",
+        if env.enable_blob_analysis {
+            "enabled"
         } else {
-            "no"
+            "disabled"
         }
     );
 
-    println!("Blobscan API endpoint: {}", env.blobscan_api_endpoint);
-    println!(
-        "CL endpoint: {:?}",
-        remove_credentials_from_url(env.beacon_node_endpoint.as_str())
-    );
-    println!(
-        "EL endpoint: {:?}",
-        remove_credentials_from_url(env.execution_node_endpoint.as_str())
-    );
+    if let Some(dry_run_output) = args.dry_run_output.clone() {
+        println!("Dry run output: {}", dry_run_output);
+    }
+
+    match env.sink {
+        SinkType::Blobscan => {
+            println!("Sink: blobscan");
+            println!("Blobscan API endpoint: {}", env.blobscan_api_endpoint);
+        }
+        SinkType::File => {
+            println!("Sink: file");
+            if let Some(sink_file_dir) = env.sink_file_dir.clone() {
+                println!("Sink directory: {}", sink_file_dir);
+            }
+        }
+        SinkType::Postgres => {
+            println!("Sink: postgres");
+            if let Some(database_url) = env.database_url.clone() {
+                println!(
+                    "Database URL: {:?}",
+                    remove_credentials_from_url(database_url.as_str())
+                );
+            }
+        }
+    }
+    match env.blob_storage {
+        Some(BlobStorageType::Filesystem) => {
+            println!("Blob storage: filesystem");
+            if let Some(blob_storage_dir) = env.blob_storage_dir.clone() {
+                println!("Blob storage directory: {}", blob_storage_dir);
+            }
+        }
+        Some(BlobStorageType::S3) => {
+            println!("Blob storage: s3");
+            if let Some(bucket) = env.blob_storage_s3_bucket.clone() {
+                println!("Blob storage bucket: {}", bucket);
+            }
+            if let Some(endpoint) = env.blob_storage_s3_endpoint.clone() {
+                println!("Blob storage endpoint: {}", endpoint);
+            }
+        }
+        None => println!("Blob storage: disabled"),
+    }
+
+    for beacon_node_endpoint in env.beacon_node_endpoints() {
+        println!(
+            "CL endpoint: {:?}",
+            remove_credentials_from_url(beacon_node_endpoint.as_str())
+        );
+    }
+    if let Some(secondary_beacon_node_endpoint) = env.secondary_beacon_node_endpoint.clone() {
+        println!(
+            "Secondary CL endpoint: {:?}",
+            remove_credentials_from_url(secondary_beacon_node_endpoint.as_str())
+        );
+    }
+
+    for execution_node_endpoint in env.execution_node_endpoints() {
+        // IPC socket paths aren't URLs and carry no credentials
+        let execution_node_endpoint =
+            remove_credentials_from_url(&execution_node_endpoint).or(Some(execution_node_endpoint));
+
+        println!("EL endpoint: {:?}", execution_node_endpoint);
+    }
+
+    if let Some(execution_node_quorum) = env.execution_node_quorum {
+        println!(
+            "EL quorum: {}/{}",
+            execution_node_quorum,
+            env.execution_node_endpoints().len()
+        );
+    }
+
+    if let Some(kzg_trusted_setup_path) = env.kzg_trusted_setup_path.clone() {
+        println!("KZG trusted setup: {}", kzg_trusted_setup_path);
+    } else {
+        println!("KZG trusted setup: bundled");
+    }
+
+    if let Some(index_batch_config) = env.index_batch_config() {
+        println!(
+            "Index batches: up to {} blocks, {} bytes, {}ms",
+            index_batch_config.max_blocks,
+            index_batch_config.max_bytes,
+            index_batch_config.max_delay.as_millis()
+        );
+    } else {
+        println!("Index batches: disabled");
+    }
+
+    if let Some(journal_path) = env.journal_path.clone() {
+        println!("Journal: {}", journal_path);
+    }
+
+    if let Some(rollup_registry_path) = env.rollup_registry_path.clone() {
+        println!("Rollup registry: {}", rollup_registry_path);
+    } else {
+        println!("Rollup registry: built-in");
+    }
+
+    if let Some(server_address) = env.server_address {
+        println!("HTTP server address: {}", server_address);
+        println!(
+            "Health check max head lag: {} slots",
+            env.health_max_head_lag
+        );
+    }
 
     if let Some(sentry_dsn) = env.sentry_dsn.clone() {
         println!("Sentry DSN: {}", sentry_dsn);
@@ -1,5 +1,6 @@
-use alloy::primitives::B256;
+use alloy::primitives::{B256, U256};
 use anyhow::{Context, Result};
+use c_kzg::{Blob as KzgBlob, Bytes48, KzgProof, KzgSettings};
 use sha2::{Digest, Sha256};
 
 const BLOB_COMMITMENT_VERSION_KZG: u8 = 0x01;
@@ -32,6 +33,146 @@ pub fn calculate_versioned_hash(commitment: &str) -> Result<B256> {
     Ok(B256::from_slice(hashed_commitment))
 }
 
+/// Computes the blob base fee resulting from the excess blob gas of a block.
+pub fn calculate_blob_base_fee(
+    excess_blob_gas: U256,
+    min_base_fee_per_blob_gas: u64,
+    base_fee_update_fraction: u64,
+) -> U256 {
+    fake_exponential(
+        U256::from(min_base_fee_per_blob_gas),
+        excess_blob_gas,
+        U256::from(base_fee_update_fraction),
+    )
+}
+
+/// Approximates `factor * e ** (numerator / denominator)` using Taylor expansion, as specified
+/// by EIP-4844.
+fn fake_exponential(factor: U256, numerator: U256, denominator: U256) -> U256 {
+    let mut i = U256::from(1);
+    let mut output = U256::ZERO;
+    let mut numerator_accum = factor * denominator;
+
+    while numerator_accum > U256::ZERO {
+        output += numerator_accum;
+        numerator_accum = (numerator_accum * numerator) / (denominator * i);
+        i += U256::from(1);
+    }
+
+    output / denominator
+}
+
 pub fn get_full_hash(hash: &B256) -> String {
     format!("0x{:x}", hash)
 }
+
+/// Computes the KZG proof of a blob against its commitment, returned as a hex string.
+pub fn compute_blob_kzg_proof(
+    blob: &[u8],
+    commitment: &str,
+    kzg_settings: &KzgSettings,
+) -> Result<String> {
+    let blob =
+        KzgBlob::from_bytes(blob).map_err(|err| anyhow::anyhow!("Invalid blob data: {err:?}"))?;
+    let commitment = Bytes48::from_hex(commitment)
+        .map_err(|err| anyhow::anyhow!("Invalid KZG commitment {commitment}: {err:?}"))?;
+    let proof = KzgProof::compute_blob_kzg_proof(&blob, &commitment, kzg_settings)
+        .map_err(|err| anyhow::anyhow!("Failed to compute KZG proof: {err:?}"))?;
+
+    Ok(format!("0x{}", proof.as_hex_string()))
+}
+
+/// Verifies that the blob data matches the given KZG commitment by checking its KZG proof.
+pub fn verify_blob_kzg_proof(
+    blob: &[u8],
+    commitment: &str,
+    proof: &str,
+    kzg_settings: &KzgSettings,
+) -> Result<bool> {
+    let blob =
+        KzgBlob::from_bytes(blob).map_err(|err| anyhow::anyhow!("Invalid blob data: {err:?}"))?;
+    let commitment = Bytes48::from_hex(commitment)
+        .map_err(|err| anyhow::anyhow!("Invalid KZG commitment {commitment}: {err:?}"))?;
+    let proof = Bytes48::from_hex(proof)
+        .map_err(|err| anyhow::anyhow!("Invalid KZG proof {proof}: {err:?}"))?;
+
+    KzgProof::verify_blob_kzg_proof(&blob, &commitment, &proof, kzg_settings)
+        .map_err(|err| anyhow::anyhow!("Failed to verify KZG proof: {err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approximates_exponentials() {
+        // Reference values from the EIP-4844 test vectors
+        let cases: [(u64, u64, u64, u64); 13] = [
+            (1, 0, 1, 1),
+            (38493, 0, 1000, 38493),
+            (0, 1234, 2345, 0),
+            (1, 2, 1, 6),
+            (1, 4, 2, 6),
+            (1, 3, 1, 16),
+            (1, 6, 2, 18),
+            (1, 4, 1, 49),
+            (1, 8, 2, 50),
+            (10, 8, 2, 542),
+            (11, 8, 2, 596),
+            (1, 5, 1, 136),
+            (1, 5, 2, 11),
+        ];
+
+        for (factor, numerator, denominator, expected) in cases {
+            assert_eq!(
+                fake_exponential(
+                    U256::from(factor),
+                    U256::from(numerator),
+                    U256::from(denominator)
+                ),
+                U256::from(expected),
+                "fake_exponential({factor}, {numerator}, {denominator})"
+            );
+        }
+
+        assert_eq!(
+            fake_exponential(U256::from(2), U256::from(5), U256::from(2)),
+            U256::from(23)
+        );
+        assert_eq!(
+            fake_exponential(U256::from(1), U256::from(50_000_000), U256::from(2_225_652)),
+            U256::from(5_709_098_764u64)
+        );
+    }
+
+    #[test]
+    fn calculates_blob_base_fees() {
+        // Minimum blob base fee without excess blob gas
+        assert_eq!(
+            calculate_blob_base_fee(U256::ZERO, 1, 3338477),
+            U256::from(1)
+        );
+        assert_eq!(
+            calculate_blob_base_fee(U256::ZERO, 1_000_000_000, 1112826),
+            U256::from(1_000_000_000)
+        );
+        // The blob base fee doubles once the excess reaches ln(2) times the update fraction
+        assert_eq!(
+            calculate_blob_base_fee(U256::from(2314056), 1, 3338477),
+            U256::from(1)
+        );
+        assert_eq!(
+            calculate_blob_base_fee(U256::from(2314058), 1, 3338477),
+            U256::from(2)
+        );
+        assert_eq!(
+            calculate_blob_base_fee(U256::from(3338477), 1, 3338477),
+            U256::from(2)
+        );
+        // 10 times the update fraction is about e^10
+        assert_eq!(
+            calculate_blob_base_fee(U256::from(33384770), 1, 3338477),
+            U256::from(22026)
+        );
+    }
+}